@@ -0,0 +1,27 @@
+//! The entry point of the plugins, generated from the metadata of the solutions
+//!
+//! Use it through `runner::export_plugin!`.
+
+use std::{env, path::PathBuf};
+
+use proc_macro::TokenStream;
+
+/// Export the solution as a plugin the runner can load with `--plugins`
+///
+/// The solution is registered from the `[package.metadata.aoc]` table of its manifest, like in
+/// the library. The macro must be called once, at the root of the crate.
+#[proc_macro]
+pub fn export_plugin(input: TokenStream) -> TokenStream {
+    if !input.is_empty() {
+        return compile_error("export_plugin! takes no arguments");
+    }
+    let dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    match discovery::plugin(&dir) {
+        Ok(code) => code.parse().expect("The generated code must be valid"),
+        Err(errors) => compile_error(&format!("Invalid solution:\n- {}", errors.join("\n- "))),
+    }
+}
+
+fn compile_error(msg: &str) -> TokenStream {
+    format!("compile_error!({msg:?});").parse().unwrap()
+}
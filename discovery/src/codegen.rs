@@ -0,0 +1,253 @@
+//! Generation of the code registering the solutions
+//!
+//! The code is written readably, as it ends up in the error messages of the compiler.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    path::Path,
+};
+
+use crate::{ExamplePart, Param, Part, Solution};
+
+/// Code written line by line, indented by blocks of 4 spaces
+#[derive(Default)]
+struct Code(String);
+impl Code {
+    fn line(&mut self, indent: usize, content: fmt::Arguments) {
+        writeln!(self.0, "{:indent$}{content}", "", indent = 4 * indent).unwrap()
+    }
+}
+
+/// The function registering the solutions in the library
+pub(crate) fn library(solutions: &BTreeMap<u16, BTreeMap<u8, Solution>>) -> String {
+    let mut code = Code::default();
+    code.line(
+        0,
+        format_args!("// Generated by the build script of the library, do not edit"),
+    );
+    code.line(0, format_args!(""));
+    code.line(
+        0,
+        format_args!("fn register(library: &mut ::runner::Library) {{"),
+    );
+    for (year, days) in solutions {
+        code.line(1, format_args!("library.add_year({year}, |year| {{"));
+        for (day, solution) in days {
+            add_day(&mut code, 2, *day, solution);
+        }
+        code.line(1, format_args!("}});"));
+    }
+    code.line(0, format_args!("}}"));
+    code.0
+}
+
+/// The entry point of the plugin of a solution, registering it in the runner loading it
+///
+/// The manifest is included so that the crate is rebuilt when the metadata changes.
+pub(crate) fn plugin(manifest: &Path, year: u16, day: u8, solution: &Solution) -> String {
+    let mut code = Code::default();
+    let manifest = path_str(manifest);
+    let symbol = format!("aoc_plugin_{}", solution.name.replace('-', "_"));
+    code.line(
+        0,
+        format_args!("const _: &str = include_str!({manifest:?});"),
+    );
+    code.line(0, format_args!(""));
+    code.line(
+        0,
+        format_args!("/// Entry point of the plugin, called by the runner loading it"),
+    );
+    code.line(0, format_args!("#[no_mangle]"));
+    code.line(
+        0,
+        format_args!(
+            "pub extern \"C\" fn {symbol}(registrar: &::runner::plugin::Registrar) -> u32 {{"
+        ),
+    );
+    code.line(
+        1,
+        format_args!("::runner::plugin::export(registrar, |library| {{"),
+    );
+    code.line(2, format_args!("library.add_year({year}, |year| {{"));
+    add_day(&mut code, 3, day, solution);
+    code.line(2, format_args!("}});"));
+    code.line(1, format_args!("}})"));
+    code.line(0, format_args!("}}"));
+    code.0
+}
+
+/// Add a day to the year being built
+fn add_day(code: &mut Code, indent: usize, day: u8, solution: &Solution) {
+    let Solution {
+        krate,
+        aoc,
+        dir,
+        examples,
+        ..
+    } = solution;
+    code.line(indent, format_args!("year.add_day({day}, |day| {{"));
+    let indent = indent + 1;
+    code.line(
+        indent,
+        format_args!("day.set_source_dir({:?});", path_str(dir)),
+    );
+    let parts = [(1, &aoc.part1), (2, &aoc.part2)];
+    let parts = parts
+        .iter()
+        .filter_map(|(num, part)| Some((num, part.as_ref()?)));
+    if let Some(parse) = &aoc.parse {
+        code.line(
+            indent,
+            format_args!("day.add_parse({krate}::{parse}, |parsed| {{"),
+        );
+        for (num, part) in parts.clone() {
+            let Part {
+                fun,
+                multiline,
+                long_running,
+                params,
+                ..
+            } = part;
+            let suffix = if params.is_empty() {
+                ""
+            } else {
+                "_with_params"
+            };
+            let fun = part_fn(krate, fun, params, true);
+            code.line(
+                indent + 1,
+                format_args!("parsed.add_part_{num}{suffix}({fun}, {multiline}, {long_running});"),
+            );
+        }
+        code.line(indent, format_args!("}});"));
+    } else {
+        for (num, part) in parts.clone() {
+            let Part {
+                fun,
+                multiline,
+                long_running,
+                params,
+                ..
+            } = part;
+            let fun = part_fn(krate, fun, params, false);
+            code.line(
+                indent,
+                format_args!("day.add_part_{num}({fun}, {multiline}, {long_running});"),
+            );
+        }
+    }
+    for (num, part) in parts {
+        if let Some(timeout) = part.timeout {
+            code.line(
+                indent,
+                format_args!(
+                    "day.set_part_timeout({num}, ::std::time::Duration::from_secs_f64({timeout:?}));"
+                ),
+            );
+        }
+        for Param { name, default, .. } in &part.params {
+            let default = default.to_string();
+            code.line(
+                indent,
+                format_args!("day.set_part_param({num}, {name:?}, {default:?});"),
+            );
+        }
+    }
+    for (idx, (example, input)) in aoc.examples.iter().zip(examples).enumerate() {
+        let name = example
+            .name
+            .as_ref()
+            .map_or_else(|| (idx + 1).to_string(), ToString::to_string);
+        code.line(
+            indent,
+            format_args!(
+                "day.add_example({name:?}, include_str!({:?}), |example| {{",
+                path_str(input)
+            ),
+        );
+        for (param, value) in &example.params {
+            let value = value.to_string();
+            code.line(
+                indent + 1,
+                format_args!("example.set_param({param:?}, {value:?});"),
+            );
+        }
+        for (num, part, ex_part) in [
+            (1, &aoc.part1, &example.part1),
+            (2, &aoc.part2, &example.part2),
+        ] {
+            let Some(ex_part) = ex_part else { continue };
+            let answer = ex_part.answer().to_string();
+            code.line(
+                indent + 1,
+                format_args!("example.expect({num}, {answer:?});"),
+            );
+            let ExamplePart::Override { fun, .. } = ex_part else {
+                continue;
+            };
+            // the override takes the same parameters as the part
+            let params = part.as_ref().map_or(&[][..], |part| &part.params);
+            let fun = match &aoc.parse {
+                Some(parse) => {
+                    let suffix = if params.is_empty() {
+                        ""
+                    } else {
+                        "_with_params"
+                    };
+                    let fun = part_fn(krate, fun, params, true);
+                    format!("::runner::PartFn::parsed{suffix}({krate}::{parse}, {fun})")
+                }
+                None => part_fn(krate, fun, params, false),
+            };
+            code.line(
+                indent + 1,
+                format_args!("example.override_part({num}, {fun});"),
+            );
+        }
+        code.line(indent, format_args!("}});"));
+    }
+    code.line(indent - 1, format_args!("}});"));
+}
+
+/// The function of a part, or if it has parameters a closure reading them and returning it
+///
+/// The parts taking the input directly are cast, to select the conversion to `PartFn`. The
+/// parameters are read before the part is timed, and cloned into each call.
+fn part_fn(krate: &str, fun: &str, params: &[Param], parsed: bool) -> String {
+    if params.is_empty() {
+        return if parsed {
+            format!("{krate}::{fun}")
+        } else {
+            format!("{krate}::{fun} as fn(&str) -> _")
+        };
+    }
+    let read: Vec<_> = params
+        .iter()
+        .map(|Param { name, ty, .. }| format!("params.get::<{ty}>({name:?})?"))
+        .collect();
+    let read = read.join(", ");
+    let args: String = (0..params.len())
+        .map(|i| format!(", ::core::clone::Clone::clone(&args.{i})"))
+        .collect();
+    let (arg, ty) = if parsed {
+        ("parsed", "&_")
+    } else {
+        ("input", "&str")
+    };
+    let prepare = format!(
+        "|params: &::runner::Params| {{ let args = ({read},); \
+         Ok(move |{arg}: {ty}| {krate}::{fun}({arg}{args})) }}"
+    );
+    if parsed {
+        prepare
+    } else {
+        format!("::runner::PartFn::with_params({prepare})")
+    }
+}
+
+/// A path written in the code, that must be valid utf-8
+fn path_str(path: &Path) -> &str {
+    path.to_str()
+        .expect("The paths of the solutions must be valid utf-8")
+}
@@ -0,0 +1,741 @@
+//! Discover the solutions and generate the code registering them in the runner
+//!
+//! Every package in `solutions/<year>/<day>` is a solution, described by the
+//! `[package.metadata.aoc]` table of its manifest:
+//! ```toml
+//! [package.metadata.aoc]
+//! parse = "parse" # optional parsing step, whose output is taken by the parts
+//!
+//! [package.metadata.aoc.part1]
+//! fun = "part1"
+//! multiline = false    # the answer spans multiple lines
+//! long_running = false # the part is too slow to be benched by default
+//! timeout = 10.0       # seconds, optional
+//! # parameters, passed after the input in this order, of types implementing FromStr and Clone
+//! params = [{ name = "width", type = "usize", default = 101 }]
+//!
+//! [[package.metadata.aoc.examples]]
+//! name = "1" # defaults to the position in the list
+//! input = "example-inputs/1.txt"
+//! params = { width = 11 } # replacing the defaults of the parts
+//! part1 = 42
+//! part2 = { answer = 12, fun = "part2_slow" } # run a different function on this example
+//! ```
+//! The metadata is validated, and the problems are reported together, pointing to the manifest.
+//!
+//! The same code is generated for the library, by its build script, and for the plugins, by
+//! `runner::export_plugin!`.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+mod codegen;
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Package,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    metadata: Option<Metadata>,
+}
+
+/// The metadata of the other tools is ignored
+#[derive(Deserialize)]
+struct Metadata {
+    aoc: Option<Aoc>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Aoc {
+    parse: Option<String>,
+    part1: Option<Part>,
+    part2: Option<Part>,
+    #[serde(default)]
+    examples: Vec<Example>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Part {
+    fun: String,
+    #[serde(default)]
+    multiline: bool,
+    #[serde(default)]
+    long_running: bool,
+    timeout: Option<f64>,
+    #[serde(default)]
+    params: Vec<Param>,
+}
+
+/// A parameter of a part, read from the `runner::Params` and passed as an argument
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Param {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    default: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Example {
+    name: Option<Literal>,
+    input: PathBuf,
+    #[serde(default)]
+    params: BTreeMap<String, Value>,
+    part1: Option<ExamplePart>,
+    part2: Option<ExamplePart>,
+}
+
+#[derive(Deserialize)]
+#[serde(
+    untagged,
+    expecting = "an answer, or a table with the `answer` and the `fun` to run"
+)]
+enum ExamplePart {
+    Answer(Literal),
+    Override { answer: Literal, fun: String },
+}
+impl ExamplePart {
+    fn answer(&self) -> &Literal {
+        match self {
+            ExamplePart::Answer(answer) | ExamplePart::Override { answer, .. } => answer,
+        }
+    }
+}
+
+/// A name or an answer, that can be written as a number
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a string or an integer")]
+enum Literal {
+    String(String),
+    Integer(i64),
+}
+impl Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::String(s) => write!(f, "{s}"),
+            Literal::Integer(i) => write!(f, "{i}"),
+        }
+    }
+}
+
+/// The value of a parameter
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a string, a number or a boolean")]
+enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{s}"),
+            Value::Integer(i) => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x}"),
+            Value::Boolean(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+/// The manifest of the library, to check the solutions are dependencies
+#[derive(Deserialize)]
+struct LibraryManifest {
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+/// A discovered solution
+struct Solution {
+    /// Name of the package
+    name: String,
+    /// Path of the crate in the generated code
+    krate: String,
+    aoc: Aoc,
+    /// Directory of the crate, searched for the input of the day
+    dir: PathBuf,
+    /// Inputs of the examples, absolute as the generated code is not next to the manifest
+    examples: Vec<PathBuf>,
+}
+
+/// The problems found in the solutions, reported together
+#[derive(Default)]
+struct Errors(Vec<String>);
+impl Errors {
+    fn push(&mut self, dir: &str, msg: impl Display) {
+        self.0.push(format!("{dir}: {msg}"))
+    }
+}
+
+/// Discover all the solutions of the workspace, and generate the function registering them
+///
+/// The code defines `fn register(library: &mut ::runner::Library)`. The solutions must be
+/// dependencies of the library, whose manifest is in `library`.
+pub fn library(library: &Path) -> Result<String, Vec<String>> {
+    let workspace = library.parent().unwrap();
+    let solutions = workspace.join("solutions");
+
+    let mut errors = Errors::default();
+    let deps = dependencies(library);
+    let mut found = BTreeMap::<u16, BTreeMap<u8, Solution>>::new();
+    // the directories of a day can be numbered differently, e.g. 1 and 01
+    let mut seen = HashMap::new();
+    for (year, year_dir) in numbered_dirs(&solutions) {
+        let rel_year = format!("solutions/{}", dir_name(&year_dir));
+        let Some(year) = u16::try_from(year).ok().filter(|year| *year >= 2015) else {
+            errors.push(&rel_year, "not an AoC year, the first was in 2015");
+            continue;
+        };
+        for (day, day_dir) in numbered_dirs(&year_dir) {
+            let rel = format!("{rel_year}/{}", dir_name(&day_dir));
+            let Some(day) = u8::try_from(day).ok().filter(|day| (1..=25).contains(day)) else {
+                errors.push(&rel, "not an advent day");
+                continue;
+            };
+            if let Some(first) = seen.insert((year, day), rel.clone()) {
+                errors.push(&rel, format_args!("the same day as {first}"));
+                continue;
+            }
+            let Some(mut solution) = discover(&day_dir, &rel, &mut errors) else {
+                continue;
+            };
+            let Some(krate) = deps.get(&day_dir.canonicalize().unwrap()) else {
+                errors.push(
+                    &rel,
+                    format_args!(
+                        "not a dependency of the library, add `{} = {{ path = \"../{rel}\" }}` \
+                         to library/Cargo.toml",
+                        solution.name
+                    ),
+                );
+                continue;
+            };
+            solution.krate = format!("::{krate}");
+            found.entry(year).or_default().insert(day, solution);
+        }
+    }
+    if !errors.0.is_empty() {
+        return Err(errors.0);
+    }
+    Ok(codegen::library(&found))
+}
+
+/// Discover the solution in `dir`, and generate the entry point of its plugin
+///
+/// The code is expanded in the solution crate itself, and defines the `extern "C"` function
+/// `aoc_plugin_<crate>` called by the runner loading the plugin.
+pub fn plugin(dir: &Path) -> Result<String, Vec<String>> {
+    let mut errors = Errors::default();
+    let numbers = (|| {
+        let day = dir.file_name()?.to_str()?.parse().ok()?;
+        let year = dir.parent()?.file_name()?.to_str()?.parse().ok()?;
+        Some((year, day))
+    })();
+    let Some((year, day)) = numbers.filter(|&(year, day)| year >= 2015 && (1..=25).contains(&day))
+    else {
+        return Err(vec![format!(
+            "{}: a plugin must be in solutions/<year>/<day>",
+            dir.display()
+        )]);
+    };
+    let rel = format!("solutions/{year}/{day}");
+    match discover(dir, &rel, &mut errors) {
+        Some(solution) if errors.0.is_empty() => Ok(codegen::plugin(
+            &dir.join("Cargo.toml"),
+            year,
+            day,
+            &solution,
+        )),
+        _ => Err(errors.0),
+    }
+}
+
+/// The dependencies of the library, by canonical path
+fn dependencies(library: &Path) -> HashMap<PathBuf, String> {
+    let manifest = fs::read_to_string(library.join("Cargo.toml")).unwrap();
+    let manifest: LibraryManifest = toml::from_str(&manifest).unwrap();
+    manifest
+        .dependencies
+        .into_iter()
+        .filter_map(|(name, dep)| {
+            let path = library
+                .join(dep.get("path")?.as_str()?)
+                .canonicalize()
+                .ok()?;
+            Some((path, name.replace('-', "_")))
+        })
+        .collect()
+}
+
+/// The subdirectories named by a number, in order
+///
+/// The others are ignored, so the solutions can be kept with notes or shared files.
+fn numbered_dirs(dir: &Path) -> Vec<(u32, PathBuf)> {
+    let mut dirs: Vec<_> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("Cannot read {}: {err}", dir.display()))
+        .filter_map(|entry| {
+            let entry = entry.unwrap();
+            let num = entry.file_name().to_str()?.parse().ok()?;
+            entry
+                .file_type()
+                .unwrap()
+                .is_dir()
+                .then(|| (num, entry.path()))
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// The name of a numbered directory, that can differ from its number, e.g. 01
+fn dir_name(dir: &Path) -> String {
+    dir.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+/// Read and validate a solution
+///
+/// The solution refers to its own crate as `crate`, until it is found in the library.
+fn discover(dir: &Path, rel: &str, errors: &mut Errors) -> Option<Solution> {
+    let manifest = match fs::read_to_string(dir.join("Cargo.toml")) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            errors.push(rel, format_args!("cannot read Cargo.toml: {err}"));
+            return None;
+        }
+    };
+    let manifest: Manifest = match toml::from_str(&manifest) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            errors.push(rel, format_args!("invalid Cargo.toml: {err}"));
+            return None;
+        }
+    };
+    let name = manifest.package.name;
+    let aoc = manifest
+        .package
+        .metadata
+        .and_then(|metadata| metadata.aoc)
+        .unwrap_or_default();
+
+    let lib = syn::parse_file(&fs::read_to_string(dir.join("src/lib.rs")).unwrap_or_default()).ok();
+    let check = |errors: &mut Errors, fun: &str, what: &dyn Display| {
+        if let Err(err) = check_fun(lib.as_ref(), fun) {
+            errors.push(rel, format_args!("{what} `{fun}` {err}"))
+        }
+    };
+    if let Some(parse) = &aoc.parse {
+        check(errors, parse, &"the parsing function");
+    }
+    for (num, part) in [(1, &aoc.part1), (2, &aoc.part2)] {
+        let Some(part) = part else { continue };
+        check(
+            errors,
+            &part.fun,
+            &format_args!("the function of part {num}"),
+        );
+        if part
+            .timeout
+            .is_some_and(|timeout| !(timeout.is_finite() && timeout > 0.))
+        {
+            errors.push(
+                rel,
+                format_args!("the timeout of part {num} must be a positive number of seconds"),
+            )
+        }
+        let mut params = HashSet::new();
+        for Param { name, ty, default } in &part.params {
+            if !params.insert(name) {
+                errors.push(
+                    rel,
+                    format_args!("part {num} has the parameter {name} twice"),
+                )
+            }
+            if let Err(err) = check_param(ty, default) {
+                errors.push(rel, format_args!("parameter {name} of part {num} {err}"))
+            }
+        }
+    }
+
+    let mut names = HashSet::new();
+    let mut examples = vec![];
+    for (idx, example) in aoc.examples.iter().enumerate() {
+        let name = example
+            .name
+            .as_ref()
+            .map_or_else(|| (idx + 1).to_string(), ToString::to_string);
+        if !names.insert(name.clone()) {
+            errors.push(rel, format_args!("the example name `{name}` is duplicated"))
+        }
+        let input = dir.join(&example.input);
+        if !input.is_file() {
+            errors.push(
+                rel,
+                format_args!(
+                    "the input of example `{name}`, {}, is not a file",
+                    example.input.display()
+                ),
+            )
+        }
+        examples.push(input);
+        if example.part1.is_none() && example.part2.is_none() {
+            errors.push(rel, format_args!("example `{name}` has no expected answer"))
+        }
+        for (num, part, ex_part) in [
+            (1, &aoc.part1, &example.part1),
+            (2, &aoc.part2, &example.part2),
+        ] {
+            let Some(ex_part) = ex_part else { continue };
+            if part.is_none() {
+                errors.push(
+                    rel,
+                    format_args!(
+                        "example `{name}` has an answer for part {num}, missing in the day"
+                    ),
+                )
+            }
+            if let ExamplePart::Override { fun, .. } = ex_part {
+                check(
+                    errors,
+                    fun,
+                    &format_args!("the function of part {num} of example `{name}`"),
+                );
+            }
+        }
+        for (param, value) in &example.params {
+            let declared: Vec<_> = [&aoc.part1, &aoc.part2]
+                .into_iter()
+                .flatten()
+                .flat_map(|part| &part.params)
+                .filter(|p| p.name == *param)
+                .collect();
+            if declared.is_empty() {
+                errors.push(
+                    rel,
+                    format_args!("example `{name}` sets {param}, a parameter of no part"),
+                )
+            }
+            for Param { ty, .. } in declared {
+                if let Err(err) = check_param(ty, value) {
+                    errors.push(
+                        rel,
+                        format_args!("parameter {param} of example `{name}` {err}"),
+                    )
+                }
+            }
+        }
+    }
+
+    Some(Solution {
+        name,
+        krate: "crate".to_owned(),
+        aoc,
+        dir: dir.to_owned(),
+        examples,
+    })
+}
+
+/// Check the type of a parameter, and that the value can be parsed as it
+///
+/// Only the values of the primitive types are checked, the others are parsed by the runner.
+fn check_param(ty: &str, value: &Value) -> Result<(), String> {
+    if syn::parse_str::<syn::Type>(ty).is_err() {
+        return Err(format!("has an invalid type `{ty}`"));
+    }
+    macro_rules! parses {
+        ($($t:ty),*) => {
+            match ty {
+                $(stringify!($t) => value.to_string().parse::<$t>().is_ok(),)*
+                _ => true,
+            }
+        };
+    }
+    if !parses!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char
+    ) {
+        return Err(format!("has the value {value}, that is not a valid {ty}"));
+    }
+    Ok(())
+}
+
+/// Check the function is a path to a public function of the solution
+///
+/// The check is skipped when the source of the solution cannot be parsed.
+fn check_fun(lib: Option<&syn::File>, fun: &str) -> Result<(), &'static str> {
+    let path = syn::parse_str::<syn::ExprPath>(fun).map_err(|_| "is not a path to a function")?;
+    if lib.is_some_and(|lib| !exports(lib, &path.path)) {
+        return Err("is not a public function of src/lib.rs");
+    }
+    Ok(())
+}
+
+/// If the path can name a public function of the file
+///
+/// Without the types, this is approximate: anything reexported with the same name is accepted,
+/// as are all the names when the items could be generated by macros.
+fn exports(file: &syn::File, path: &syn::Path) -> bool {
+    fn in_use(tree: &syn::UseTree, name: &syn::Ident) -> bool {
+        match tree {
+            syn::UseTree::Path(path) => in_use(&path.tree, name),
+            syn::UseTree::Name(used) => used.ident == *name,
+            syn::UseTree::Rename(used) => used.rename == *name,
+            syn::UseTree::Glob(_) => true,
+            syn::UseTree::Group(group) => group.items.iter().any(|tree| in_use(tree, name)),
+        }
+    }
+
+    let name = &path.segments[0].ident;
+    let in_module = path.segments.len() > 1;
+    let public = |vis: &syn::Visibility| matches!(vis, syn::Visibility::Public(_));
+    file.items.iter().any(|item| match item {
+        syn::Item::Fn(fun) => !in_module && public(&fun.vis) && fun.sig.ident == *name,
+        syn::Item::Mod(module) => in_module && public(&module.vis) && module.ident == *name,
+        syn::Item::Use(used) => public(&used.vis) && in_use(&used.tree, name),
+        // the entry point of the plugin defines no part
+        syn::Item::Macro(mac) => {
+            mac.ident.is_none()
+                && mac
+                    .mac
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident != "export_plugin")
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    const LIB: &str = "
+        pub fn parse(input: &str) -> usize { input.len() }
+        pub fn part1(len: &usize, width: usize) -> usize { len * width }
+    ";
+
+    const VALID: &str = r#"
+        [package.metadata.aoc]
+        parse = "parse"
+
+        [package.metadata.aoc.part1]
+        fun = "part1"
+        timeout = 10.0
+        params = [{ name = "width", type = "usize", default = 101 }]
+
+        [[package.metadata.aoc.examples]]
+        input = "example-inputs/1.txt"
+        params = { width = 11 }
+        part1 = 33
+    "#;
+
+    /// A workspace with the solutions in the given directories, removed when dropped
+    struct Workspace(PathBuf);
+    impl Workspace {
+        /// The solutions, all dependencies of the library, have the given metadata
+        fn new(solutions: &[(&str, &str)]) -> Self {
+            // the tests run concurrently
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let count = COUNT.fetch_add(1, Ordering::Relaxed);
+            let root = env::temp_dir().join(format!("aoc-discovery-{}-{count}", process::id()));
+            let _ = fs::remove_dir_all(&root);
+            let mut deps = String::new();
+            for (idx, (rel, metadata)) in solutions.iter().enumerate() {
+                let dir = root.join(rel);
+                fs::create_dir_all(dir.join("src")).unwrap();
+                fs::create_dir_all(dir.join("example-inputs")).unwrap();
+                let name = format!("day{idx}");
+                let manifest = format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n");
+                fs::write(dir.join("Cargo.toml"), manifest + metadata).unwrap();
+                fs::write(dir.join("src/lib.rs"), LIB).unwrap();
+                fs::write(dir.join("example-inputs/1.txt"), "abc").unwrap();
+                deps += &format!("{name} = {{ path = \"../{rel}\" }}\n");
+            }
+            fs::create_dir_all(root.join("solutions")).unwrap();
+            fs::create_dir_all(root.join("library")).unwrap();
+            let manifest = format!("[package]\nname = \"library\"\n\n[dependencies]\n{deps}");
+            fs::write(root.join("library/Cargo.toml"), manifest).unwrap();
+            Self(root)
+        }
+
+        fn library(&self) -> Result<String, Vec<String>> {
+            library(&self.0.join("library"))
+        }
+    }
+    impl Drop for Workspace {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// The errors of a single solution in 2015/1
+    fn errors(metadata: &str) -> Vec<String> {
+        Workspace::new(&[("solutions/2015/1", metadata)])
+            .library()
+            .unwrap_err()
+    }
+
+    #[test]
+    fn valid() {
+        let workspace = Workspace::new(&[("solutions/2015/1", VALID), ("solutions/2016/25", "")]);
+        let code = workspace.library().unwrap();
+        assert!(code.contains("library.add_year(2015, |year| {"), "{code}");
+        assert!(code.contains("library.add_year(2016, |year| {"), "{code}");
+        assert!(code.contains("::day0::part1"), "{code}");
+        // the others are ignored
+        fs::create_dir_all(workspace.0.join("solutions/2015/notes")).unwrap();
+        fs::write(workspace.0.join("solutions/2015/3"), "not a directory").unwrap();
+        assert_eq!(workspace.library().unwrap(), code);
+
+        let entry = plugin(&workspace.0.join("solutions/2015/1")).unwrap();
+        assert!(
+            entry.contains("pub extern \"C\" fn aoc_plugin_day0("),
+            "{entry}"
+        );
+        let err = plugin(&workspace.0.join("library")).unwrap_err();
+        assert!(
+            err[0].ends_with("a plugin must be in solutions/<year>/<day>"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn layout() {
+        let workspace = Workspace::new(&[
+            ("solutions/2014/1", ""),
+            ("solutions/2015/0", ""),
+            ("solutions/2015/26", ""),
+            ("solutions/2015/1", ""),
+            ("solutions/2015/01", ""),
+        ]);
+        assert_eq!(
+            workspace.library().unwrap_err(),
+            [
+                "solutions/2014: not an AoC year, the first was in 2015",
+                "solutions/2015/0: not an advent day",
+                "solutions/2015/1: the same day as solutions/2015/01",
+                "solutions/2015/26: not an advent day",
+            ]
+        );
+
+        let workspace = Workspace::new(&[("solutions/2015/1", "")]);
+        fs::write(workspace.0.join("library/Cargo.toml"), "[dependencies]").unwrap();
+        assert_eq!(
+            workspace.library().unwrap_err(),
+            ["solutions/2015/1: not a dependency of the library, add \
+              `day0 = { path = \"../solutions/2015/1\" }` to library/Cargo.toml"]
+        );
+    }
+
+    #[test]
+    fn malformed_manifests() {
+        let err = errors("[package.metadata.aoc.part3]\nfun = \"part1\"");
+        assert!(err[0].starts_with("solutions/2015/1: invalid Cargo.toml: "));
+        assert!(err[0].contains("unknown field `part3`"), "{err:?}");
+
+        let err = errors("[package.metadata.aoc.part1]\nfun = 1");
+        assert!(err[0].starts_with("solutions/2015/1: invalid Cargo.toml: "));
+        let err = errors("[package.metadata.aoc");
+        assert!(err[0].starts_with("solutions/2015/1: invalid Cargo.toml: "));
+        let err = errors(
+            "[[package.metadata.aoc.examples]]\ninput = \"example-inputs/1.txt\"\npart1 = [1]",
+        );
+        assert!(err[0].contains("an answer, or a table with"), "{err:?}");
+
+        let workspace = Workspace::new(&[("solutions/2015/1", "")]);
+        fs::remove_file(workspace.0.join("solutions/2015/1/Cargo.toml")).unwrap();
+        let err = workspace.library().unwrap_err();
+        assert!(err[0].starts_with("solutions/2015/1: cannot read Cargo.toml: "));
+    }
+
+    #[test]
+    fn invalid_metadata() {
+        let part = "[package.metadata.aoc.part1]\nfun = \"part1\"\n";
+        let example = "[[package.metadata.aoc.examples]]\ninput = \"example-inputs/1.txt\"\n";
+        let cases = [
+            (
+                "[package.metadata.aoc]\nparse = \"parse2\"".to_owned(),
+                "the parsing function `parse2` is not a public function of src/lib.rs",
+            ),
+            (
+                "[package.metadata.aoc.part2]\nfun = \"part 2\"".to_owned(),
+                "the function of part 2 `part 2` is not a path to a function",
+            ),
+            (
+                format!("{part}timeout = 0.0"),
+                "the timeout of part 1 must be a positive number of seconds",
+            ),
+            (
+                format!(
+                    "{part}params = [{{ name = \"n\", type = \"u8\", default = 1 }}, \
+                     {{ name = \"n\", type = \"u8\", default = 2 }}]"
+                ),
+                "part 1 has the parameter n twice",
+            ),
+            (
+                format!("{part}params = [{{ name = \"n\", type = \"u8\", default = 300 }}]"),
+                "parameter n of part 1 has the value 300, that is not a valid u8",
+            ),
+            (
+                format!("{part}params = [{{ name = \"n\", type = \"Vec<\", default = 1 }}]"),
+                "parameter n of part 1 has an invalid type `Vec<`",
+            ),
+            (
+                format!("{part}{example}part1 = 1\n{example}name = 1\npart1 = 2"),
+                "the example name `1` is duplicated",
+            ),
+            (
+                format!("{part}{example}input = \"missing.txt\"\npart1 = 1")
+                    .replace("input = \"example-inputs/1.txt\"\n", ""),
+                "the input of example `1`, missing.txt, is not a file",
+            ),
+            (
+                format!("{part}{example}"),
+                "example `1` has no expected answer",
+            ),
+            (
+                format!("{part}{example}part2 = 1"),
+                "example `1` has an answer for part 2, missing in the day",
+            ),
+            (
+                format!("{part}{example}part1 = {{ answer = 1, fun = \"part1_slow\" }}"),
+                "the function of part 1 of example `1` `part1_slow` is not a public function of \
+                 src/lib.rs",
+            ),
+            (
+                format!("{part}{example}part1 = 1\nparams = {{ n = 1 }}"),
+                "example `1` sets n, a parameter of no part",
+            ),
+            (
+                format!(
+                    "{part}params = [{{ name = \"n\", type = \"bool\", default = true }}]\n\
+                     {example}part1 = 1\nparams = {{ n = 1 }}"
+                ),
+                "parameter n of example `1` has the value 1, that is not a valid bool",
+            ),
+        ];
+        for (metadata, expected) in cases {
+            assert_eq!(
+                errors(&metadata),
+                [format!("solutions/2015/1: {expected}")],
+                "{metadata}"
+            );
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Discover the solutions and generate the code registering them in the runner
+//!
+//! The metadata of the solutions is described in the `discovery` crate. Any problem fails the
+//! build with a message pointing to the manifest. The generated code is in
+//! `$OUT_DIR/solutions.rs`.
+
+use std::{env, fs, path::PathBuf, process::exit};
+
+fn main() {
+    let library = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!(
+        "cargo:rerun-if-changed={}",
+        library.parent().unwrap().join("solutions").display()
+    );
+
+    let code = match discovery::library(&library) {
+        Ok(code) => code,
+        Err(errors) => {
+            eprintln!("Invalid solutions:");
+            for error in &errors {
+                eprintln!("- {error}");
+            }
+            exit(1)
+        }
+    };
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("solutions.rs");
+    fs::write(out, code).expect("Cannot write the generated code");
+}
@@ -0,0 +1,9 @@
+//! The runner, with all the solutions of the workspace
+//!
+//! The solutions are discovered by the build script from their manifests.
+
+include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+
+fn main() {
+    runner::run(register, std::env::args_os())
+}
@@ -0,0 +1,465 @@
+//! Answers of the solutions, and the type erased functions computing them
+
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display},
+    hint::black_box,
+    panic::RefUnwindSafe,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    memory::{self, MemoryStats},
+    ParamError, Params,
+};
+
+/// A value that can be the answer of a part
+///
+/// The rendering is what gets compared with the known answers, so it must be exactly what would be
+/// submitted on the site.
+pub trait Answer {
+    fn to_answer(&self) -> String;
+}
+
+macro_rules! display_answer {
+    ($($t:ty),*) => {$(
+        impl Answer for $t {
+            fn to_answer(&self) -> String {
+                self.to_string()
+            }
+        }
+    )*};
+}
+display_answer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, char, str, String);
+
+/// Letters, e.g. the ones read from a display
+impl<const N: usize> Answer for [u8; N] {
+    fn to_answer(&self) -> String {
+        self.as_slice().to_answer()
+    }
+}
+/// Letters, e.g. the ones read from a display
+impl Answer for [u8] {
+    fn to_answer(&self) -> String {
+        String::from_utf8(self.to_vec()).expect("The solution must return valid utf-8")
+    }
+}
+/// Letters, e.g. the ones read from a display
+impl Answer for Vec<u8> {
+    fn to_answer(&self) -> String {
+        self.as_slice().to_answer()
+    }
+}
+/// A missing answer is rendered as `None`, so it never matches the correct one
+impl<T: Answer> Answer for Option<T> {
+    fn to_answer(&self) -> String {
+        match self {
+            Some(answer) => answer.to_answer(),
+            None => "None".to_owned(),
+        }
+    }
+}
+impl<T: Answer + ?Sized> Answer for &T {
+    fn to_answer(&self) -> String {
+        (**self).to_answer()
+    }
+}
+impl<T: Answer + ?Sized> Answer for Box<T> {
+    fn to_answer(&self) -> String {
+        (**self).to_answer()
+    }
+}
+
+/// Wrapper using the [`Display`] implementation of a type as the answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Displayed<T>(pub T);
+impl<T: Display> Answer for Displayed<T> {
+    fn to_answer(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// What a solution returns: an answer, or a [`Result`] of one
+///
+/// An error is not a wrong answer but a failure of the solution, e.g. a parser rejecting the
+/// input. It is reported with its whole chain of sources, including the contexts of `anyhow`.
+pub trait Outcome {
+    /// The rendered answer, or the chain of messages of the error, outermost first
+    fn into_answer(self) -> Result<String, Vec<String>>;
+}
+impl<T: Answer> Outcome for T {
+    fn into_answer(self) -> Result<String, Vec<String>> {
+        Ok(self.to_answer())
+    }
+}
+impl<T: Answer, E: Into<Box<dyn Error>>> Outcome for Result<T, E> {
+    fn into_answer(self) -> Result<String, Vec<String>> {
+        self.map(|answer| answer.to_answer()).map_err(|err| {
+            let err = err.into();
+            let mut chain = vec![err.to_string()];
+            let mut source = err.source();
+            while let Some(err) = source {
+                chain.push(err.to_string());
+                source = err.source();
+            }
+            chain
+        })
+    }
+}
+
+/// The rendered answer or the chain of the error, the time taken by the solution and its heap
+/// usage, if measured
+pub(crate) type Call = (Result<String, Vec<String>>, Duration, Option<MemoryStats>);
+/// Compute the answer on an input, with the parameters
+type CallFn = dyn Fn(&str, &Params) -> Call + Send + Sync + RefUnwindSafe;
+/// Run the solution some times on an input, with the parameters, returning the elapsed time
+type RunFn = dyn Fn(&str, &Params, u32) -> Duration + Send + Sync + RefUnwindSafe;
+/// Check that the solution can read the parameters
+type CheckFn = dyn Fn(&Params) -> Result<(), ParamError> + Send + Sync + RefUnwindSafe;
+
+/// The inputs parsed by a parsing step, shared by the parts working on its output
+///
+/// Each input is parsed once, by the first part running on it, and the parts running on it at the
+/// same time wait for it. The parsed inputs are kept until they are cleared.
+pub(crate) struct ParsedInputs<P> {
+    parse: fn(&str) -> P,
+    parsed: Mutex<HashMap<String, Arc<OnceLock<Arc<P>>>>>,
+}
+impl<P> ParsedInputs<P> {
+    pub(crate) fn new(parse: fn(&str) -> P) -> Self {
+        Self {
+            parse,
+            parsed: Mutex::default(),
+        }
+    }
+
+    /// The parsed input, parsing it if no part did yet
+    fn get(&self, input: &str) -> Arc<P> {
+        let cell = {
+            let mut parsed = self.parsed.lock().unwrap_or_else(PoisonError::into_inner);
+            match parsed.get(input) {
+                Some(cell) => cell.clone(),
+                None => parsed.entry(input.to_owned()).or_default().clone(),
+            }
+        };
+        // parsing without the lock, so the other inputs can be parsed at the same time
+        cell.get_or_init(|| Arc::new((self.parse)(input))).clone()
+    }
+}
+
+/// The parsed inputs of a day, with their type erased
+pub(crate) trait ParsedCache: Debug + Send + Sync + RefUnwindSafe {
+    /// Drop all the parsed inputs
+    fn clear(&self);
+}
+impl<P: Send + Sync> ParsedCache for ParsedInputs<P> {
+    fn clear(&self) {
+        self.parsed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear()
+    }
+}
+impl<P> Debug for ParsedInputs<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "inputs parsed as {}", std::any::type_name::<P>())
+    }
+}
+
+/// A solution of a part, with the type of the answer erased
+///
+/// The parts of a day with a parsing step share the parsed inputs, and the parsing is not part of
+/// their timings. Parts can take [`Params`], that they read before being timed.
+#[derive(Clone)]
+pub struct PartFn {
+    signature: String,
+    call: Arc<CallFn>,
+    run: Arc<RunFn>,
+    check: Arc<CheckFn>,
+    /// Inputs parsed for the function, if it works on the output of a parsing step
+    parsed: Option<Arc<dyn ParsedCache>>,
+}
+impl PartFn {
+    /// A part working on the output of a parsing step
+    ///
+    /// The part can take anything the parsed input can be borrowed as, e.g. a slice of a vector.
+    pub fn parsed<P, B, T>(parse: fn(&str) -> P, fun: fn(&B) -> T) -> Self
+    where
+        P: Borrow<B> + Send + Sync + 'static,
+        B: ?Sized + 'static,
+        T: Outcome + 'static,
+    {
+        Self::parsed_with(Arc::new(ParsedInputs::new(parse)), move |_: &Params| {
+            Ok(fun)
+        })
+    }
+
+    /// A part working on the output of a parsing step, and taking parameters
+    ///
+    /// `prepare` reads the parameters, and returns the function of the part taking them.
+    pub fn parsed_with_params<P, B, T, F, G>(parse: fn(&str) -> P, prepare: F) -> Self
+    where
+        P: Borrow<B> + Send + Sync + 'static,
+        B: ?Sized + 'static,
+        T: Outcome + 'static,
+        F: Fn(&Params) -> Result<G, ParamError> + Copy + Send + Sync + RefUnwindSafe + 'static,
+        G: Fn(&B) -> T,
+    {
+        Self::parsed_with(Arc::new(ParsedInputs::new(parse)), prepare)
+    }
+
+    /// A part taking the input and parameters
+    ///
+    /// `prepare` reads the parameters, and returns the function of the part taking them.
+    pub fn with_params<T, F, G>(prepare: F) -> Self
+    where
+        T: Outcome + 'static,
+        F: Fn(&Params) -> Result<G, ParamError> + Copy + Send + Sync + RefUnwindSafe + 'static,
+        G: Fn(&str) -> T,
+    {
+        Self::unparsed(prepare)
+    }
+
+    /// A part working on inputs parsed once, and shared with the other parts using `parsed`
+    pub(crate) fn parsed_with<P, B, T, F, G>(parsed: Arc<ParsedInputs<P>>, prepare: F) -> Self
+    where
+        P: Borrow<B> + Send + Sync + 'static,
+        B: ?Sized + 'static,
+        T: Outcome + 'static,
+        F: Fn(&Params) -> Result<G, ParamError> + Copy + Send + Sync + RefUnwindSafe + 'static,
+        G: Fn(&B) -> T,
+    {
+        let run_parsed = parsed.clone();
+        let cache = parsed.clone();
+        Self {
+            signature: format!(
+                "fn(&{}) -> {}",
+                std::any::type_name::<B>(),
+                std::any::type_name::<T>()
+            ),
+            call: Arc::new(move |input, params| {
+                let fun = match prepare(params) {
+                    Ok(fun) => fun,
+                    Err(err) => return invalid_params(err),
+                };
+                let parsed = parsed.get(input);
+                let ((outcome, elapsed), memory) = memory::measure(|| {
+                    let s = Instant::now();
+                    let outcome = fun((*parsed).borrow());
+                    (outcome, s.elapsed())
+                });
+                (outcome.into_answer(), elapsed, memory)
+            }),
+            run: Arc::new(move |input, params, iters| {
+                let fun = prepared(prepare, params);
+                let parsed = run_parsed.get(input);
+                let s = Instant::now();
+                for _ in 0..iters {
+                    black_box((fun)(black_box((*parsed).borrow())));
+                }
+                s.elapsed()
+            }),
+            check: Arc::new(move |params| prepare(params).map(drop)),
+            parsed: Some(cache),
+        }
+    }
+
+    /// A parsing step, measured on its own
+    ///
+    /// Its answer is always empty.
+    pub(crate) fn parse<P: 'static>(parse: fn(&str) -> P) -> Self {
+        Self {
+            signature: format!("fn(&str) -> {}", std::any::type_name::<P>()),
+            call: Arc::new(move |input, _| {
+                let (elapsed, memory) = memory::measure(|| {
+                    let s = Instant::now();
+                    black_box(parse(input));
+                    s.elapsed()
+                });
+                (Ok(String::new()), elapsed, memory)
+            }),
+            run: Arc::new(move |input, _, iters| {
+                let s = Instant::now();
+                for _ in 0..iters {
+                    black_box((parse)(black_box(input)));
+                }
+                s.elapsed()
+            }),
+            check: Arc::new(|_| Ok(())),
+            parsed: None,
+        }
+    }
+
+    fn unparsed<T, F, G>(prepare: F) -> Self
+    where
+        T: Outcome + 'static,
+        F: Fn(&Params) -> Result<G, ParamError> + Copy + Send + Sync + RefUnwindSafe + 'static,
+        G: Fn(&str) -> T,
+    {
+        Self {
+            signature: format!("fn(&str) -> {}", std::any::type_name::<T>()),
+            call: Arc::new(move |input, params| {
+                let fun = match prepare(params) {
+                    Ok(fun) => fun,
+                    Err(err) => return invalid_params(err),
+                };
+                let ((outcome, elapsed), memory) = memory::measure(|| {
+                    let s = Instant::now();
+                    let outcome = fun(input);
+                    (outcome, s.elapsed())
+                });
+                (outcome.into_answer(), elapsed, memory)
+            }),
+            run: Arc::new(move |input, params, iters| {
+                let fun = prepared(prepare, params);
+                let s = Instant::now();
+                for _ in 0..iters {
+                    black_box((fun)(black_box(input)));
+                }
+                s.elapsed()
+            }),
+            check: Arc::new(move |params| prepare(params).map(drop)),
+            parsed: None,
+        }
+    }
+
+    /// A function implemented elsewhere, e.g. in a plugin
+    ///
+    /// Its parameters are checked when it is called.
+    pub(crate) fn from_fns<C, R>(signature: String, call: C, run: R) -> Self
+    where
+        C: Fn(&str, &Params) -> Call + Send + Sync + RefUnwindSafe + 'static,
+        R: Fn(&str, &Params, u32) -> Duration + Send + Sync + RefUnwindSafe + 'static,
+    {
+        Self {
+            signature,
+            call: Arc::new(call),
+            run: Arc::new(run),
+            check: Arc::new(|_| Ok(())),
+            parsed: None,
+        }
+    }
+
+    /// Compute the answer, or the chain of the error returned by the solution
+    ///
+    /// Also return the time taken by the solution and its heap usage, without the rendering of the
+    /// answer.
+    pub(crate) fn call(&self, input: &str, params: &Params) -> Call {
+        (self.call)(input, params)
+    }
+
+    /// Run the function `iters` times, returning the total elapsed time
+    ///
+    /// The answers are not rendered, only passed to [`black_box`].
+    pub(crate) fn run(&self, input: &str, params: &Params, iters: u32) -> Duration {
+        (self.run)(input, params, iters)
+    }
+
+    /// Check that the function can read the parameters
+    pub(crate) fn check_params(&self, params: &Params) -> Result<(), ParamError> {
+        (self.check)(params)
+    }
+
+    /// The inputs parsed for the function, if it works on the output of a parsing step
+    pub(crate) fn parsed_inputs(&self) -> Option<Arc<dyn ParsedCache>> {
+        self.parsed.clone()
+    }
+}
+impl<T: Outcome + 'static> From<fn(&str) -> T> for PartFn {
+    fn from(fun: fn(&str) -> T) -> Self {
+        Self::unparsed(move |_: &Params| Ok(fun))
+    }
+}
+/// A part returning a slice of its input, whose type depends on the lifetime of the input
+// the impl above only takes answers outliving the input, `rustc` lints them as overlapping
+#[allow(coherence_leak_check)]
+impl From<for<'a> fn(&'a str) -> &'a str> for PartFn {
+    fn from(fun: for<'a> fn(&'a str) -> &'a str) -> Self {
+        Self {
+            signature: "fn(&str) -> &str".to_owned(),
+            call: Arc::new(move |input, _| {
+                let ((answer, elapsed), memory) = memory::measure(|| {
+                    let s = Instant::now();
+                    let answer = fun(input);
+                    (answer, s.elapsed())
+                });
+                (Ok(answer.to_owned()), elapsed, memory)
+            }),
+            run: Arc::new(move |input, _, iters| {
+                let s = Instant::now();
+                for _ in 0..iters {
+                    black_box(fun(black_box(input)));
+                }
+                s.elapsed()
+            }),
+            check: Arc::new(|_| Ok(())),
+            parsed: None,
+        }
+    }
+}
+
+/// The outcome of a part whose parameters cannot be read, without running it
+fn invalid_params(err: ParamError) -> Call {
+    (Err(vec![err.to_string()]), Duration::ZERO, None)
+}
+
+/// The function of a part, whose parameters were already checked by its first call
+fn prepared<G>(prepare: impl Fn(&Params) -> Result<G, ParamError>, params: &Params) -> G {
+    prepare(params).unwrap_or_else(|err| panic!("{err}"))
+}
+impl Debug for PartFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn parts_borrowing_the_input() {
+        fn first_line(input: &str) -> &str {
+            input.lines().next().unwrap_or_default()
+        }
+        let fun = PartFn::from(first_line as for<'a> fn(&'a str) -> &'a str);
+        let params = Params::default();
+
+        assert_eq!(fun.call("abc\ndef", &params).0, Ok("abc".to_owned()));
+        fun.run("abc\ndef", &params, 10);
+        assert_eq!(format!("{fun:?}"), "fn(&str) -> &str");
+    }
+
+    #[test]
+    fn parts_share_the_parsed_input() {
+        static PARSED: AtomicUsize = AtomicUsize::new(0);
+        fn parse(input: &str) -> Vec<u32> {
+            PARSED.fetch_add(1, Ordering::Relaxed);
+            input.lines().map(|l| l.parse().unwrap()).collect()
+        }
+        let parsed = Arc::new(ParsedInputs::new(parse));
+        let sum = PartFn::parsed_with(parsed.clone(), |_: &Params| {
+            Ok(|v: &[u32]| v.iter().sum::<u32>())
+        });
+        let max = PartFn::parsed_with(parsed.clone(), |_: &Params| {
+            Ok(|v: &[u32]| v.iter().max().copied())
+        });
+        let params = Params::default();
+
+        assert_eq!(sum.call("1\n2\n3", &params).0, Ok("6".to_owned()));
+        sum.run("1\n2\n3", &params, 10);
+        assert_eq!(max.call("1\n2\n3", &params).0, Ok("3".to_owned()));
+        assert_eq!(PARSED.load(Ordering::Relaxed), 1);
+        assert_eq!(max.call("4", &params).0, Ok("4".to_owned()));
+        assert_eq!(PARSED.load(Ordering::Relaxed), 2);
+
+        parsed.clear();
+        assert_eq!(sum.call("4", &params).0, Ok("4".to_owned()));
+        assert_eq!(PARSED.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,481 @@
+//! The known answers of the parts, stored in `answers.json`
+//!
+//! The file has the two parts of each day, or of each input of the days with named inputs. A part
+//! is `null` if nothing is known of it, its correct answer, or an object also listing the answers
+//! the site rejected:
+//!
+//! ```json
+//! {"2024": {"17": [
+//!     "4,6,3,5,6,3,5,2,1,0",
+//!     {"wrong": ["117440"], "too_high": ["2024"], "too_low": ["5"]}
+//! ]}}
+//! ```
+//!
+//! A numeric answer above a rejected "too high" one, or below a "too low" one, is certainly wrong
+//! too, so a new answer can be known to be wrong before the correct one is.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs::{self, File},
+    io,
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::{filters::Filters, Library, Measures, PerInput, DEFAULT_INPUT};
+
+/// The known answers, by year, day and input
+pub(crate) type Answers = BTreeMap<u16, BTreeMap<u8, PerInput<[KnownAnswers; 2]>>>;
+
+pub(crate) fn read(file: impl AsRef<Path>) -> anyhow::Result<Answers> {
+    let file = File::open(file).context("Cannot open answer file")?;
+    serde_json::from_reader(file).context("Cannot parse answer file")
+}
+
+/// Read the answers to edit them, a missing file having none
+fn read_existing(file: &Path) -> anyhow::Result<Answers> {
+    match fs::metadata(file) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Answers::new()),
+        _ => read(file),
+    }
+}
+
+fn write(file: &Path, answers: &Answers) -> anyhow::Result<()> {
+    let mut content =
+        serde_json::to_string_pretty(answers).context("Cannot serialize the answers")?;
+    content.push('\n');
+    fs::write(file, content).with_context(|| format!("Cannot write {}", file.display()))
+}
+
+/// What is known of the answers of a part
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Repr", into = "Repr")]
+pub(crate) struct KnownAnswers {
+    pub(crate) correct: Option<String>,
+    /// Answers rejected without a hint
+    wrong: Vec<String>,
+    /// Numeric answers rejected as too high
+    too_high: Vec<String>,
+    /// Numeric answers rejected as too low
+    too_low: Vec<String>,
+}
+
+/// Serialized form of the known answers, the shortest one for what is known
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    Unknown(()),
+    Correct(String),
+    Full {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correct: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        wrong: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        too_high: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        too_low: Vec<String>,
+    },
+}
+impl From<Repr> for KnownAnswers {
+    fn from(repr: Repr) -> Self {
+        match repr {
+            Repr::Unknown(()) => Self::default(),
+            Repr::Correct(correct) => Self {
+                correct: Some(correct),
+                ..Self::default()
+            },
+            Repr::Full {
+                correct,
+                wrong,
+                too_high,
+                too_low,
+            } => Self {
+                correct,
+                wrong,
+                too_high,
+                too_low,
+            },
+        }
+    }
+}
+impl From<KnownAnswers> for Repr {
+    fn from(known: KnownAnswers) -> Self {
+        match known {
+            KnownAnswers {
+                correct: None,
+                wrong,
+                too_high,
+                too_low,
+            } if wrong.is_empty() && too_high.is_empty() && too_low.is_empty() => Repr::Unknown(()),
+            KnownAnswers {
+                correct: Some(correct),
+                wrong,
+                too_high,
+                too_low,
+            } if wrong.is_empty() && too_high.is_empty() && too_low.is_empty() => {
+                Repr::Correct(correct)
+            }
+            KnownAnswers {
+                correct,
+                wrong,
+                too_high,
+                too_low,
+            } => Repr::Full {
+                correct,
+                wrong,
+                too_high,
+                too_low,
+            },
+        }
+    }
+}
+
+/// What the known answers say of an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Check {
+    Unknown,
+    Correct,
+    /// Different from the correct answer
+    Wrong,
+    /// Rejected by the site, with the reason
+    Rejected(String),
+}
+impl Check {
+    pub(crate) fn correct(&self) -> Option<bool> {
+        match self {
+            Check::Unknown => None,
+            Check::Correct => Some(true),
+            Check::Wrong | Check::Rejected(_) => Some(false),
+        }
+    }
+}
+
+/// A hint given by the site with a wrong answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+impl KnownAnswers {
+    /// Nothing is known of the answers
+    pub(crate) const UNKNOWN: Self = Self {
+        correct: None,
+        wrong: vec![],
+        too_high: vec![],
+        too_low: vec![],
+    };
+
+    fn is_empty(&self) -> bool {
+        *self == Self::UNKNOWN
+    }
+
+    pub(crate) fn check(&self, answer: &str) -> Check {
+        match &self.correct {
+            Some(correct) if correct == answer => Check::Correct,
+            Some(_) => Check::Wrong,
+            None => self
+                .rejection(answer)
+                .map_or(Check::Unknown, Check::Rejected),
+        }
+    }
+
+    /// Why the answer was or would be rejected, ignoring the correct answer
+    fn rejection(&self, answer: &str) -> Option<String> {
+        if self.wrong.iter().any(|w| w == answer) {
+            return Some("already rejected".to_owned());
+        }
+        if self.too_high.iter().any(|w| w == answer) {
+            return Some("already rejected as too high".to_owned());
+        }
+        if self.too_low.iter().any(|w| w == answer) {
+            return Some("already rejected as too low".to_owned());
+        }
+        let value = numeric(answer)?;
+        if let Some(bound) = self.too_high.iter().filter_map(|w| numeric(w)).min() {
+            if value > bound {
+                return Some(format!("too high, {bound} already was"));
+            }
+        }
+        if let Some(bound) = self.too_low.iter().filter_map(|w| numeric(w)).max() {
+            if value < bound {
+                return Some(format!("too low, {bound} already was"));
+            }
+        }
+        None
+    }
+
+    fn accept(&mut self, answer: &str) -> anyhow::Result<()> {
+        if let Some(reason) = self.rejection(answer) {
+            bail!("{answer} is certainly wrong: {reason}")
+        }
+        match self.correct.replace(answer.to_owned()) {
+            Some(previous) if previous != answer => {
+                log::warn!("Replacing the previous correct answer {previous}")
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn reject(&mut self, answer: &str, hint: Option<Hint>) -> anyhow::Result<()> {
+        if self.correct.as_deref() == Some(answer) {
+            bail!("{answer} is recorded as the correct answer")
+        }
+        if hint.is_some() && numeric(answer).is_none() {
+            bail!("Only numeric answers can be too high or too low")
+        }
+        let rejected = match hint {
+            None => &mut self.wrong,
+            Some(Hint::TooHigh) => &mut self.too_high,
+            Some(Hint::TooLow) => &mut self.too_low,
+        };
+        if !rejected.iter().any(|r| r == answer) {
+            rejected.push(answer.to_owned());
+        }
+        Ok(())
+    }
+
+    /// The contradictions between the known answers
+    fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if let Some(correct) = &self.correct {
+            if let Some(reason) = self.rejection(correct) {
+                problems.push(format!("the correct answer {correct} is {reason}"));
+            }
+        }
+        let highest_too_low = self.too_low.iter().filter_map(|w| numeric(w)).max();
+        let lowest_too_high = self.too_high.iter().filter_map(|w| numeric(w)).min();
+        if let Some((low, high)) = highest_too_low.zip(lowest_too_high) {
+            if low >= high {
+                problems.push(format!("{low} is too low but {high} is too high"));
+            }
+        }
+        for (kind, answers) in [("too high", &self.too_high), ("too low", &self.too_low)] {
+            for answer in answers.iter().filter(|a| numeric(a).is_none()) {
+                problems.push(format!("{answer} is not numeric, but was {kind}"));
+            }
+        }
+        problems
+    }
+}
+
+fn numeric(answer: &str) -> Option<i128> {
+    answer.trim().parse().ok()
+}
+
+/// A single part, as `YEAR::DAY::PART`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PartSpec {
+    year: u16,
+    day: u8,
+    part: u8,
+}
+impl PartSpec {
+    pub(crate) fn new(year: u16, day: u8, part: u8) -> Self {
+        Self { year, day, part }
+    }
+
+    /// The filters selecting only this part
+    pub(crate) fn filters(&self) -> Filters {
+        self.to_string()
+            .parse()
+            .expect("A single part must be a valid filter")
+    }
+}
+impl FromStr for PartSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [year, day, part] = s
+            .split("::")
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Expected YEAR::DAY::PART"))?;
+        let spec = Self {
+            year: year.parse().context("Cannot parse year")?,
+            day: day.parse().context("Cannot parse day")?,
+            part: part.parse().context("Cannot parse part")?,
+        };
+        if spec.year < 2015 || !(1..=25).contains(&spec.day) || !(1..=2).contains(&spec.part) {
+            bail!("There is no part {spec}")
+        }
+        Ok(spec)
+    }
+}
+impl Display for PartSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}::{}", self.year, self.day, self.part)
+    }
+}
+
+/// How to edit the known answers of a part
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Edit {
+    Accept,
+    Reject(Option<Hint>),
+}
+
+/// Record the answers of a part as correct or rejected
+///
+/// Without a given answer, the part is run with `measure` on its inputs, or only on `input` if
+/// given, and its answers are recorded. A given answer is recorded for `input`, by default the
+/// default input.
+pub(crate) fn edit(
+    file: &Path,
+    library: &Library,
+    spec: PartSpec,
+    answer: Option<String>,
+    input: Option<String>,
+    edit: Edit,
+    measure: impl FnOnce() -> Measures,
+) -> anyhow::Result<()> {
+    let PartSpec { year, day, part } = spec;
+    let has_part = library
+        .solutions
+        .get(&year)
+        .and_then(|y| y.solutions.get(&day))
+        .is_some_and(|d| d.parts[part as usize - 1].is_some());
+    if !has_part {
+        bail!("There is no solution of {spec}")
+    }
+
+    let computed: BTreeMap<String, String> = match answer {
+        Some(answer) => BTreeMap::from([(
+            input.clone().unwrap_or_else(|| DEFAULT_INPUT.to_owned()),
+            answer,
+        )]),
+        None => {
+            let mut measures = measure();
+            let day_inputs = measures
+                .get_mut(&year)
+                .and_then(|y| y.remove(&day))
+                .context("The part was not run")?
+                .context("Cannot read the inputs")?;
+            let mut computed = BTreeMap::new();
+            for (name, meas) in day_inputs {
+                if input.as_ref().is_some_and(|input| *input != name) {
+                    continue;
+                }
+                let mut parts = meas.parts;
+                let answer = parts[part as usize - 1]
+                    .take()
+                    .context("The part was not run")?
+                    .map_err(|err| anyhow::anyhow!("{err}"))
+                    .with_context(|| format!("The part failed on input {name}"))?;
+                computed.insert(name, answer.answer);
+            }
+            if let Some(input) = &input {
+                if computed.is_empty() {
+                    bail!("There is no input {input}")
+                }
+            }
+            computed
+        }
+    };
+    record(file, spec, computed, edit).map(|_| ())
+}
+
+/// Record answers of a part by input, returning all the known answers
+pub(crate) fn record(
+    file: &Path,
+    spec: PartSpec,
+    computed: BTreeMap<String, String>,
+    edit: Edit,
+) -> anyhow::Result<Answers> {
+    let mut answers = read_existing(file)?;
+    let PartSpec { year, day, part } = spec;
+    let day_answers = answers.entry(year).or_default().entry(day).or_default();
+    for (name, answer) in computed {
+        let known = &mut day_answers.0.entry(name.clone()).or_default()[part as usize - 1];
+        let result = match edit {
+            Edit::Accept => known.accept(&answer),
+            Edit::Reject(hint) => known.reject(&answer, hint),
+        };
+        result.with_context(|| format!("Cannot record the answer of {spec} on input {name}"))?;
+        let on_input = if name == DEFAULT_INPUT {
+            String::new()
+        } else {
+            format!(" on input {name}")
+        };
+        match edit {
+            Edit::Accept => log::info!("Accepted {answer} for {spec}{on_input}"),
+            Edit::Reject(_) => log::info!("Rejected {answer} for {spec}{on_input}"),
+        }
+    }
+    write(file, &answers)?;
+    Ok(answers)
+}
+
+/// Check the answers against the library, then print them formatted or rewrite the file
+pub(crate) fn check(
+    file: &Path,
+    library: &Library,
+    inputs: &Path,
+    rewrite: bool,
+) -> anyhow::Result<()> {
+    let answers = read(file)?;
+    let problems = validate(&answers, library, inputs);
+    if !problems.is_empty() {
+        bail!("Invalid answers:\n- {}", problems.join("\n- "))
+    }
+    if rewrite {
+        write(file, &answers)?;
+        log::info!("Formatted {}", file.display());
+    } else {
+        let content =
+            serde_json::to_string_pretty(&answers).context("Cannot serialize the answers")?;
+        println!("{content}");
+    }
+    Ok(())
+}
+
+/// Describe the answers of missing days, parts or inputs, and the contradicting ones
+fn validate(answers: &Answers, library: &Library, inputs: &Path) -> Vec<String> {
+    let mut problems = vec![];
+    for (year, days) in answers {
+        for (day, day_answers) in days {
+            let Some(solution) = library
+                .solutions
+                .get(year)
+                .and_then(|y| y.solutions.get(day))
+            else {
+                problems.push(format!("{year}::{day}: no solution is registered"));
+                continue;
+            };
+            // the inputs that cannot be read are reported when running
+            if let Ok(names) = solution.read_inputs(inputs) {
+                for name in day_answers.0.keys() {
+                    if !names.contains_key(name) {
+                        problems.push(format!("{year}::{day}: there is no input {name}"));
+                    }
+                }
+            }
+            for (name, parts) in &day_answers.0 {
+                let on_input = if name == DEFAULT_INPUT {
+                    String::new()
+                } else {
+                    format!(" on input {name}")
+                };
+                for (part, known) in (1..).zip(parts) {
+                    if known.is_empty() {
+                        continue;
+                    }
+                    if solution.parts[part - 1].is_none() {
+                        problems.push(format!("{year}::{day}: there is no part {part}"));
+                    }
+                    for problem in known.problems() {
+                        problems.push(format!("{year}::{day}::{part}{on_input}: {problem}"));
+                    }
+                }
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,300 @@
+//! Statistical measurement of the running time of the solutions
+
+use std::{num::NonZeroU32, ops::Add, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Minimum duration of a single sample
+///
+/// Faster solutions are run multiple times in each sample, so the timer resolution does not
+/// dominate the measurement.
+const MIN_SAMPLE_TIME: Duration = Duration::from_micros(100);
+
+/// Quantile of the normal distribution used for the 95% confidence intervals
+const Z_95: f64 = 1.96;
+
+/// Settings of the measurement engine
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BenchSettings {
+    /// Untimed runs before sampling, in addition to the run computing the answer
+    pub(crate) warmup: u32,
+    /// Minimum number of samples
+    pub(crate) min_samples: NonZeroU32,
+    /// Maximum number of samples
+    pub(crate) max_samples: NonZeroU32,
+    /// Keep sampling until this much time is spent
+    pub(crate) budget: Option<Duration>,
+    /// Keep sampling until the 95% confidence interval of the mean is narrower than this fraction
+    /// of the mean
+    pub(crate) target_ci: Option<f64>,
+}
+impl BenchSettings {
+    /// Arguments passing these settings to a child runner
+    pub(crate) fn to_args(self) -> Vec<String> {
+        let mut args = vec![
+            "--repeats".to_owned(),
+            self.min_samples.to_string(),
+            "--warmup".to_owned(),
+            self.warmup.to_string(),
+            "--max-samples".to_owned(),
+            self.max_samples.to_string(),
+        ];
+        if let Some(budget) = self.budget {
+            args.push("--time-budget".to_owned());
+            args.push(humantime::format_duration(budget).to_string());
+        }
+        if let Some(target_ci) = self.target_ci {
+            args.push("--target-ci".to_owned());
+            args.push(target_ci.to_string());
+        }
+        args
+    }
+
+    /// Measure a function
+    ///
+    /// `run(iters)` must run the solution `iters` times, returning the total elapsed time.
+    /// `first_run` is the time the run computing the answer took, used to size the samples.
+    pub(crate) fn measure(
+        &self,
+        mut run: impl FnMut(u32) -> Duration,
+        first_run: Duration,
+    ) -> TimeStats {
+        for _ in 0..self.warmup {
+            run(1);
+        }
+
+        let iters = (MIN_SAMPLE_TIME.as_nanos() / first_run.max(Duration::from_nanos(1)).as_nanos())
+            .clamp(1, u32::MAX as _) as u32;
+
+        let mut samples = vec![];
+        let mut spent = Duration::ZERO;
+        loop {
+            let elapsed = run(iters);
+            spent += elapsed;
+            samples.push(elapsed.as_secs_f64() / iters as f64);
+
+            let n = samples.len() as u32;
+            if n >= self.max_samples.get() {
+                break;
+            }
+            if n < self.min_samples.get() {
+                continue;
+            }
+            if self.budget.is_none() && self.target_ci.is_none() {
+                break;
+            }
+            if self.budget.is_some_and(|budget| spent >= budget) {
+                break;
+            }
+            if self
+                .target_ci
+                .is_some_and(|target| relative_ci(&samples).is_some_and(|ci| ci <= target))
+            {
+                break;
+            }
+        }
+        TimeStats::from_samples(samples)
+    }
+}
+
+/// Relative half-width of the 95% confidence interval of the mean
+fn relative_ci(samples: &[f64]) -> Option<f64> {
+    let (mean, stddev) = mean_stddev(samples)?;
+    (mean > 0.).then(|| Z_95 * stddev / (samples.len() as f64).sqrt() / mean)
+}
+
+fn mean_stddev(samples: &[f64]) -> Option<(f64, f64)> {
+    let n = samples.len() as f64;
+    if samples.len() < 2 {
+        return None;
+    }
+    let mean = samples.iter().sum::<f64>() / n;
+    let var = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.);
+    Some((mean, var.sqrt()))
+}
+
+/// Statistics on the running time of a solution
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TimeStats {
+    /// Number of samples taken
+    ///
+    /// With less than 2 samples the variance is unknown.
+    pub(crate) samples: u32,
+    pub(crate) min: Duration,
+    pub(crate) median: Duration,
+    pub(crate) mean: Duration,
+    pub(crate) stddev: Duration,
+    pub(crate) p95: Duration,
+}
+impl TimeStats {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        assert!(!samples.is_empty());
+        samples.sort_by(f64::total_cmp);
+        let (mean, stddev) = mean_stddev(&samples).unwrap_or((samples[0], 0.));
+        // nearest rank quantiles
+        let quantile = |q: f64| samples[((q * samples.len() as f64).ceil() as usize).max(1) - 1];
+        Self {
+            samples: samples.len() as u32,
+            min: Duration::from_secs_f64(samples[0]),
+            median: Duration::from_secs_f64(quantile(0.5)),
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(stddev),
+            p95: Duration::from_secs_f64(quantile(0.95)),
+        }
+    }
+
+    /// Statistics of a single measurement
+    pub(crate) fn single(time: Duration) -> Self {
+        Self {
+            samples: 1,
+            min: time,
+            median: time,
+            mean: time,
+            stddev: Duration::ZERO,
+            p95: time,
+        }
+    }
+
+    /// Pool together the statistics of two measurements of the same solution
+    ///
+    /// Counts, means and variances are exact, while the quantiles are weighted averages.
+    pub(crate) fn merge(&self, other: &Self) -> Self {
+        let (n1, n2) = (self.samples as f64, other.samples as f64);
+        let n = n1 + n2;
+        let weighted = |a: Duration, b: Duration| {
+            Duration::from_secs_f64((a.as_secs_f64() * n1 + b.as_secs_f64() * n2) / n)
+        };
+        let (m1, m2) = (self.mean.as_secs_f64(), other.mean.as_secs_f64());
+        let var = ((n1 - 1.) * self.stddev.as_secs_f64().powi(2)
+            + (n2 - 1.) * other.stddev.as_secs_f64().powi(2)
+            + n1 * n2 / n * (m1 - m2).powi(2))
+            / (n - 1.);
+        Self {
+            samples: self.samples + other.samples,
+            min: self.min.min(other.min),
+            median: weighted(self.median, other.median),
+            mean: weighted(self.mean, other.mean),
+            stddev: Duration::from_secs_f64(var.sqrt()),
+            p95: weighted(self.p95, other.p95),
+        }
+    }
+
+    /// Check if the difference with a previous measurement is statistically significant
+    ///
+    /// This is a Welch test at 95% confidence. Without variance information on both sides any
+    /// difference is considered significant.
+    pub(crate) fn differs_from(&self, previous: &Self) -> bool {
+        if self.samples < 2 || previous.samples < 2 {
+            return self.mean != previous.mean;
+        }
+        let se = (self.stddev.as_secs_f64().powi(2) / self.samples as f64
+            + previous.stddev.as_secs_f64().powi(2) / previous.samples as f64)
+            .sqrt();
+        (self.mean.as_secs_f64() - previous.mean.as_secs_f64()).abs() > Z_95 * se
+    }
+}
+
+/// Statistics of the sum of two independent running times
+///
+/// Means and variances are exact, while the sums of the quantiles are only an estimate.
+impl Add for TimeStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            samples: self.samples.min(rhs.samples),
+            min: self.min + rhs.min,
+            median: self.median + rhs.median,
+            mean: self.mean + rhs.mean,
+            stddev: Duration::from_secs_f64(
+                (self.stddev.as_secs_f64().powi(2) + rhs.stddev.as_secs_f64().powi(2)).sqrt(),
+            ),
+            p95: self.p95 + rhs.p95,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::TimeStats;
+
+    fn stats(samples: &[f64]) -> TimeStats {
+        TimeStats::from_samples(samples.to_vec())
+    }
+
+    fn assert_secs(duration: Duration, secs: f64) {
+        assert!(
+            (duration.as_secs_f64() - secs).abs() < 1e-9,
+            "{duration:?} is not {secs}s"
+        )
+    }
+
+    #[test]
+    fn from_samples() {
+        let stats = stats(&[5., 1., 4., 2., 3.]);
+        assert_eq!(stats.samples, 5);
+        assert_secs(stats.min, 1.);
+        assert_secs(stats.median, 3.);
+        assert_secs(stats.mean, 3.);
+        assert_secs(stats.stddev, 2.5f64.sqrt());
+        assert_secs(stats.p95, 5.);
+    }
+
+    #[test]
+    fn merge_is_exact_on_mean_and_variance() {
+        let merged = stats(&[1., 2., 3.]).merge(&stats(&[4., 5.]));
+        let all = stats(&[1., 2., 3., 4., 5.]);
+        assert_eq!(merged.samples, 5);
+        assert_eq!(merged.min, all.min);
+        assert_secs(merged.mean, all.mean.as_secs_f64());
+        assert_secs(merged.stddev, all.stddev.as_secs_f64());
+    }
+
+    #[test]
+    fn merge_single_samples() {
+        let merged = TimeStats::single(Duration::from_secs(1))
+            .merge(&TimeStats::single(Duration::from_secs(3)));
+        assert_eq!(merged.samples, 2);
+        assert_secs(merged.mean, 2.);
+        assert_secs(merged.stddev, 2f64.sqrt());
+    }
+
+    #[test]
+    fn add() {
+        let sum = stats(&[1., 2., 3.]) + stats(&[10., 10., 10., 14.]);
+        assert_eq!(sum.samples, 3);
+        assert_secs(sum.min, 11.);
+        assert_secs(sum.mean, 13.);
+        assert_secs(sum.stddev, (1f64 + 4.).sqrt());
+    }
+
+    #[test]
+    fn single_samples_differ_on_any_change() {
+        let one = TimeStats::single(Duration::from_secs(1));
+        assert!(!one.differs_from(&one));
+        assert!(one.differs_from(&TimeStats::single(Duration::from_millis(1001))));
+        // a single sample on one side is enough to have no variance
+        assert!(stats(&[1., 2., 3.]).differs_from(&TimeStats::single(Duration::from_millis(2001))));
+    }
+
+    #[test]
+    fn zero_variance() {
+        let ones = stats(&[1., 1., 1.]);
+        assert_secs(ones.stddev, 0.);
+        assert!(!ones.differs_from(&ones));
+        assert!(ones.differs_from(&stats(&[2., 2., 2.])));
+    }
+
+    #[test]
+    fn welch_test() {
+        // the standard error of the difference is 1s
+        let base = stats(&[4., 5., 6., 7., 8.]);
+        let shifted = |by: f64| stats(&[4. + by, 5. + by, 6. + by, 7. + by, 8. + by]);
+        assert!(!shifted(0.5).differs_from(&base));
+        assert!(!shifted(-1.9).differs_from(&base));
+        assert!(shifted(2.).differs_from(&base));
+        assert!(shifted(-3.).differs_from(&base));
+    }
+}
@@ -0,0 +1,187 @@
+//! Configuration file of the runner
+//!
+//! `aoc-runner.toml`, in the directory the runner is started from, gives the defaults of the
+//! options of the command line, by their long name, and named queries in its `presets` table:
+//!
+//! ```toml
+//! inputs = "./inputs"
+//! baselines-dir = "./baselines"
+//! format = "html"
+//! jobs = 4
+//! timeout = "30s"
+//!
+//! [presets]
+//! quick = "..::..::1 & !long_running"
+//! ```
+//!
+//! The options given on the command line override the file, and `@quick` selects the problems of
+//! the preset.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use anyhow::{bail, Context};
+use clap::{builder::ValueParser, Arg, ArgAction, Command};
+use serde::Deserialize;
+
+use crate::filters::{Filters, Presets};
+
+/// Configuration file, in the current directory
+const CONFIG_FILE: &str = "aoc-runner.toml";
+
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    #[serde(default)]
+    presets: Presets,
+    /// Default values of the options, by long name
+    #[serde(flatten)]
+    defaults: BTreeMap<String, toml::Value>,
+}
+
+/// Read the configuration, if the file exists
+fn read(path: &Path) -> anyhow::Result<Config> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(err) => return Err(err.into()),
+    };
+    Ok(toml::from_str(&content)?)
+}
+
+/// The command line parser, with the defaults and presets of the configuration file
+pub(crate) fn apply(command: Command) -> anyhow::Result<Command> {
+    apply_from(command, Path::new(CONFIG_FILE))
+}
+
+/// The command line parser, with the defaults and presets of the configuration file at `path`
+fn apply_from(command: Command, path: &Path) -> anyhow::Result<Command> {
+    let file = path.display();
+    let Config { presets, defaults } = read(path).with_context(|| format!("Cannot read {file}"))?;
+    for (name, query) in &presets {
+        Filters::parse_with(query, &presets, Some(path))
+            .with_context(|| format!("Invalid preset {name} in {file}"))?;
+    }
+
+    let problems = {
+        let presets = presets.clone();
+        let path = path.to_owned();
+        ValueParser::new(move |s: &str| Filters::parse_with(s, &presets, Some(&path)))
+    };
+    let mut command = command
+        .mut_arg("problems", |arg| arg.value_parser(problems.clone()))
+        .mut_subcommand("compare", |compare| {
+            compare.mut_arg("problems", |arg| arg.value_parser(problems))
+        });
+    for (name, value) in defaults {
+        let id = name.replace('-', "_");
+        let Some(arg) = command
+            .get_arguments()
+            .find(|arg| arg.get_id() == id.as_str())
+        else {
+            bail!("Unknown option {name} in {file}")
+        };
+        let values =
+            to_strings(arg, value).with_context(|| format!("Invalid value of {name} in {file}"))?;
+        command = command.mut_arg(id, |arg| arg.default_values(values));
+    }
+    Ok(command)
+}
+
+/// The values of an option, as given on the command line
+fn to_strings(arg: &Arg, value: toml::Value) -> anyhow::Result<Vec<String>> {
+    if arg.is_hide_set() {
+        bail!("The option is internal")
+    }
+    let multiple = matches!(arg.get_action(), ArgAction::Append);
+    let values = match value {
+        toml::Value::Array(values) if multiple => values,
+        toml::Value::Array(_) => bail!("The option takes a single value"),
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            Ok(match value {
+                toml::Value::String(s) => s,
+                toml::Value::Integer(i) => i.to_string(),
+                toml::Value::Float(f) => f.to_string(),
+                toml::Value::Boolean(b) => b.to_string(),
+                toml::Value::Datetime(d) => d.to_string(),
+                toml::Value::Array(_) | toml::Value::Table(_) => bail!("Expected a single value"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use clap::{CommandFactory, FromArgMatches};
+
+    use super::*;
+    use crate::Args;
+
+    /// Parse the command line with a configuration file
+    fn parse(config: Option<&str>, args: &[&str]) -> anyhow::Result<Args> {
+        // the tests run concurrently
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+        let name = format!("aoc-runner-{}-{count}.toml", process::id());
+        let path = env::temp_dir().join(name);
+        let _ = fs::remove_file(&path);
+        if let Some(config) = config {
+            fs::write(&path, config)?;
+        }
+        let command = apply_from(Args::command(), &path);
+        let _ = fs::remove_file(&path);
+        let matches = command?.try_get_matches_from([&"runner"].into_iter().chain(args))?;
+        Ok(Args::from_arg_matches(&matches)?)
+    }
+
+    const CONFIG: &str = r#"
+        jobs = 4
+        format = "json"
+
+        [presets]
+        quick = "2015 - long_running"
+        first = "@quick & ..::..::1"
+    "#;
+
+    #[test]
+    fn overrides() {
+        let args = parse(Some(CONFIG), &[]).unwrap();
+        assert_eq!(args.jobs, 4);
+        let args = parse(Some(CONFIG), &["--jobs", "2"]).unwrap();
+        assert_eq!(args.jobs, 2);
+        let args = parse(None, &[]).unwrap();
+        assert_eq!(args.jobs, 1);
+
+        let err = parse(Some("jobs = [1, 2]"), &[]).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid value of jobs in "));
+        let err = parse(Some("isolated-worker = true"), &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid value of isolated-worker in "));
+        let err = parse(Some("bogus = 1"), &[]).unwrap_err();
+        assert!(err.to_string().starts_with("Unknown option bogus in "));
+    }
+
+    #[test]
+    fn presets() {
+        let args = parse(Some(CONFIG), &["@first - 2015::3"]).unwrap();
+        assert_eq!(
+            args.problems.to_string(),
+            "((2015-long_running)&..::..::1)-2015::3"
+        );
+
+        let err = parse(Some("[presets]\nloop = \"@loop\""), &[]).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid preset loop in "));
+        // the file is named even if it does not exist
+        let err = parse(None, &["@quick"]).unwrap_err().to_string();
+        assert!(err.contains("unknown preset `@quick` in "), "{err}");
+        assert!(err.contains("aoc-runner-"), "{err}");
+    }
+}
@@ -1,7 +1,33 @@
-use anyhow::{bail, Context};
-use std::{collections::BTreeSet, fmt::Display, str::FromStr};
+//! Selection of the problems to run
+//!
+//! A query combines:
+//! - selections of parts, `YEARS::DAYS::PARTS`, each a comma separated list of numbers and ranges
+//!   like `3`, `1..5`, `..5`, `3..` or `..`, e.g. `2022`, `2022::1,3,7` or `2015..2016::..10::2`;
+//! - predicates on the metadata of the parts, `long_running` and `multiline`;
+//! - predicates on the last known status of the parts, from the baseline: `correct`, `wrong`,
+//!   `unknown` if the correctness of the answer is not known, and `slow>DURATION`, e.g.
+//!   `slow>100ms`.
+//!
+//! They are combined with `|` (union), `&` (intersection) and `-` (exclusion), from the loosest
+//! to the tightest, negated with `!` and grouped with parentheses, e.g. `2022 - 2022::19` or
+//! `2024 & !(correct | long_running)`.
+//!
+//! `@NAME` stands for the query of a preset of the configuration file, e.g. `@quick - 2015`.
+//!
+//! A year or a day is selected if any of its parts could be. A predicate that cannot be known
+//! where the query is used, e.g. the metadata of the parts when comparing baselines, does not
+//! exclude anything.
 
-use itertools::Itertools;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{Answers, Baselines};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 enum Range<T> {
@@ -24,36 +50,11 @@ where
         }
     }
 }
-impl<T> FromStr for Range<T>
-where
-    T: FromStr + Clone,
-    T::Err: Send + Sync + 'static + std::error::Error,
-{
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(
-            match s.split_once("..").map(|(a, b)| (a.trim(), b.trim())) {
-                Some(("", "")) => Self::All,
-                Some(("", b)) => Self::To(b.parse().context("Cannot parse end value")?),
-                Some((a, "")) => Self::From(a.parse().context("Cannot parse starting value")?),
-                Some((a, b)) => Self::Between(
-                    a.parse().context("Cannot parse starting value")?,
-                    b.parse().context("Cannot parse end value")?,
-                ),
-                None => {
-                    let a: T = s.parse().context("Cannot parse value")?;
-                    Self::Between(a.clone(), a)
-                }
-            },
-        )
-    }
-}
 impl<T> Display for Range<T>
 where
     T: Display + Eq,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Range::All => write!(f, "..")?,
             Range::From(a) => {
@@ -75,159 +76,732 @@ where
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-enum PartFilter {
-    First,
-    Second,
-    #[default]
-    Both,
+/// Union of ranges
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct List<T>(Vec<Range<T>>);
+impl<T: Ord + Copy> List<T> {
+    fn all() -> Self {
+        Self(vec![Range::All])
+    }
+
+    fn is_all(&self) -> bool {
+        self.0.contains(&Range::All)
+    }
+
+    fn accept(&self, value: T) -> bool {
+        self.0.iter().any(|r| r.accept(value))
+    }
 }
-impl PartFilter {
-    fn accept(&self, part: u8) -> bool {
-        assert!(part == 1 || part == 2);
-        match self {
-            PartFilter::First => part == 1,
-            PartFilter::Second => part == 2,
-            PartFilter::Both => true,
+impl<T: Display + Eq> Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, range) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            range.fmt(f)?;
         }
+        Ok(())
     }
 }
-impl FromStr for PartFilter {
-    type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim();
-        Ok(
-            match s.split_once("..").map(|(a, b)| (a.trim(), b.trim())) {
-                Some(("", "1")) | Some(("1", "1")) => Self::First,
-                Some(("2", "")) | Some(("2", "2")) => Self::Second,
-                Some(("", "2")) | Some(("1", "2")) | Some(("1", "")) => Self::Both,
-                Some(("2", "1")) => bail!("Invalid part order"),
-                Some(("" | "1" | "2", _)) => bail!("Unrecognized end part"),
-                Some((_, "" | "1" | "2")) => bail!("Unrecognized start part"),
-                Some((_, _)) => bail!("Unrecognized limit parts"),
-                None => match s {
-                    "1" => Self::First,
-                    "2" => Self::Second,
-                    _ => bail!("Unrecognized part"),
-                },
-            },
-        )
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Predicate {
+    LongRunning,
+    Multiline,
+    Correct,
+    Wrong,
+    Unknown,
+    /// The running time in the baseline is longer than this
+    Slower(Duration),
 }
-impl Display for PartFilter {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PartFilter::First => write!(f, "1"),
-            PartFilter::Second => write!(f, "2"),
-            PartFilter::Both => write!(f, ".."),
+            Predicate::LongRunning => write!(f, "long_running"),
+            Predicate::Multiline => write!(f, "multiline"),
+            Predicate::Correct => write!(f, "correct"),
+            Predicate::Wrong => write!(f, "wrong"),
+            Predicate::Unknown => write!(f, "unknown"),
+            Predicate::Slower(time) => write!(f, "slow>{}", humantime::format_duration(*time)),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub struct SimpleFilter {
-    years: Range<u16>,
-    day: Range<u8>,
-    part: PartFilter,
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Parts {
+        years: List<u16>,
+        days: List<u8>,
+        parts: List<u8>,
+    },
+    Predicate(Predicate),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
 }
-impl SimpleFilter {
-    fn accept_year(&self, year: u16) -> bool {
-        self.years.accept(year)
+impl Expr {
+    /// If the subject is selected, `None` if it depends on what is not known of it
+    fn eval(&self, subject: &Subject, status: &Status) -> Option<bool> {
+        match self {
+            Expr::Parts { years, days, parts } => and([
+                Some(years.accept(subject.year)),
+                match subject.day {
+                    Some(day) => Some(days.accept(day)),
+                    None => days.is_all().then_some(true),
+                },
+                match subject.part {
+                    Some(part) => Some(parts.accept(part)),
+                    None => parts.is_all().then_some(true),
+                },
+            ]),
+            Expr::Predicate(predicate) => subject.check(*predicate, status),
+            Expr::Not(expr) => expr.eval(subject, status).map(|selected| !selected),
+            Expr::And(exprs) => and(exprs.iter().map(|e| e.eval(subject, status))),
+            Expr::Or(exprs) => {
+                let mut result = Some(false);
+                for selected in exprs.iter().map(|e| e.eval(subject, status)) {
+                    match selected {
+                        Some(true) => return Some(true),
+                        Some(false) => (),
+                        None => result = None,
+                    }
+                }
+                result
+            }
+        }
     }
 
-    fn accept_day(&self, year: u16, day: u8) -> bool {
-        self.accept_year(year) && self.day.accept(day)
+    /// Write the expression, in parentheses if it binds looser than `precedence`
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        let own = match self {
+            Expr::Or(_) => 0,
+            Expr::And(_) => 1,
+            _ => 2,
+        };
+        if own < precedence {
+            write!(f, "(")?;
+        }
+        let (sep, pipe, amp, minus) = if f.alternate() {
+            (" :: ", " | ", " & ", " - ")
+        } else {
+            ("::", "|", "&", "-")
+        };
+        match self {
+            Expr::Parts { years, days, parts } => {
+                years.fmt(f)?;
+                if !days.is_all() || !parts.is_all() {
+                    write!(f, "{sep}")?;
+                    days.fmt(f)?;
+                }
+                if !parts.is_all() {
+                    write!(f, "{sep}")?;
+                    parts.fmt(f)?;
+                }
+            }
+            Expr::Predicate(predicate) => predicate.fmt(f)?,
+            Expr::Not(expr) => {
+                write!(f, "!")?;
+                expr.fmt_with(f, 2)?;
+            }
+            Expr::And(exprs) => {
+                for (i, expr) in exprs.iter().enumerate() {
+                    match expr {
+                        // written back as an exclusion
+                        Expr::Not(excluded) if i > 0 => {
+                            write!(f, "{minus}")?;
+                            excluded.fmt_with(f, 2)?;
+                        }
+                        _ => {
+                            if i > 0 {
+                                write!(f, "{amp}")?;
+                            }
+                            expr.fmt_with(f, 2)?;
+                        }
+                    }
+                }
+            }
+            Expr::Or(exprs) => {
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{pipe}")?;
+                    }
+                    expr.fmt_with(f, 1)?;
+                }
+            }
+        }
+        if own < precedence {
+            write!(f, ")")?;
+        }
+        Ok(())
     }
+}
 
-    fn accept_part(&self, year: u16, day: u8, part: u8) -> bool {
-        self.accept_day(year, day) && self.part.accept(part)
+/// Intersection of selections, `None` if it depends on what is not known
+fn and(selected: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    let mut result = Some(true);
+    for selected in selected {
+        match selected {
+            Some(true) => (),
+            Some(false) => return Some(false),
+            None => result = None,
+        }
     }
+    result
 }
-impl FromStr for SimpleFilter {
-    type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.splitn(3, "::").map(str::trim);
-        Ok(Self {
-            years: parts
-                .next()
-                .map(str::parse)
-                .transpose()
-                .context("Cannot parse year range")?
-                .unwrap_or_default(),
-            day: parts
-                .next()
-                .map(str::parse)
-                .transpose()
-                .context("Cannot parse day range")?
-                .unwrap_or_default(),
-            part: parts
-                .next()
-                .map(str::parse)
-                .transpose()
-                .context("Cannot parse part filter")?
-                .unwrap_or_default(),
-        })
-    }
+/// Metadata of a part, used by the predicates on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PartMeta {
+    pub(crate) multiline: bool,
+    pub(crate) long_running: bool,
 }
-impl Display for SimpleFilter {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.years.fmt(f)?;
-        if self.day != Range::All || self.part != PartFilter::Both {
-            if f.alternate() {
-                write!(f, " :: ")
-            } else {
-                write!(f, "::")
-            }?;
-            self.day.fmt(f)?;
-        }
-        if self.part != PartFilter::Both {
-            if f.alternate() {
-                write!(f, " :: ")
-            } else {
-                write!(f, "::")
-            }?;
-            self.part.fmt(f)?;
+
+/// Last known status of a part, over all its inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct PartStatus {
+    correct: Option<bool>,
+    /// Longest running time on an input
+    time: Option<Duration>,
+}
+
+type Status = BTreeMap<(u16, u8, u8), PartStatus>;
+
+/// Queries by name
+pub(crate) type Presets = BTreeMap<String, String>;
+
+/// What is being filtered, and what is known of it
+struct Subject {
+    year: u16,
+    day: Option<u8>,
+    part: Option<u8>,
+    meta: Option<PartMeta>,
+}
+impl Subject {
+    fn check(&self, predicate: Predicate, status: &Status) -> Option<bool> {
+        match predicate {
+            Predicate::LongRunning => self.meta.map(|m| m.long_running),
+            Predicate::Multiline => self.meta.map(|m| m.multiline),
+            Predicate::Correct | Predicate::Wrong | Predicate::Unknown | Predicate::Slower(_) => {
+                let key = (self.year, self.day?, self.part?);
+                let status = status.get(&key).copied().unwrap_or_default();
+                Some(match predicate {
+                    Predicate::Correct => status.correct == Some(true),
+                    Predicate::Wrong => status.correct == Some(false),
+                    Predicate::Unknown => status.correct.is_none(),
+                    Predicate::Slower(limit) => status.time.is_some_and(|time| time > limit),
+                    Predicate::LongRunning | Predicate::Multiline => unreachable!(),
+                })
+            }
         }
-        Ok(())
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct Filters(BTreeSet<SimpleFilter>);
+pub struct Filters {
+    expr: Expr,
+    status: Status,
+}
 impl Filters {
     pub(crate) fn accept_year(&self, year: u16) -> bool {
-        self.0.iter().any(|f| f.accept_year(year))
+        self.accept(Subject {
+            year,
+            day: None,
+            part: None,
+            meta: None,
+        })
     }
 
     pub(crate) fn accept_day(&self, year: u16, day: u8) -> bool {
-        self.0.iter().any(|f| f.accept_day(year, day))
+        self.accept(Subject {
+            year,
+            day: Some(day),
+            part: None,
+            meta: None,
+        })
     }
 
-    pub(crate) fn accept_part(&self, year: u16, day: u8, part: u8) -> bool {
-        self.0.iter().any(|f| f.accept_part(year, day, part))
+    /// Check a part, with its metadata if known
+    pub(crate) fn accept_part(&self, year: u16, day: u8, part: u8, meta: Option<PartMeta>) -> bool {
+        assert!(part == 1 || part == 2);
+        self.accept(Subject {
+            year,
+            day: Some(day),
+            part: Some(part),
+            meta,
+        })
+    }
+
+    /// Parse a query that can refer to presets, read from the file `source`
+    pub(crate) fn parse_with(
+        s: &str,
+        presets: &Presets,
+        source: Option<&Path>,
+    ) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            input: s,
+            pos: 0,
+            presets,
+            source,
+            expanding: vec![],
+        };
+        Ok(Self {
+            expr: parser.query()?,
+            status: Status::new(),
+        })
+    }
+
+    fn accept(&self, subject: Subject) -> bool {
+        self.expr.eval(&subject, &self.status) != Some(false)
+    }
+
+    /// Set the last known status of the parts, from a baseline and the known answers
+    ///
+    /// A part is wrong if it is on any input, and correct if it is on all of them.
+    pub(crate) fn set_status(&mut self, baseline: Option<&Baselines>, answers: &Answers) {
+        self.status.clear();
+        let Some(baseline) = baseline else { return };
+        for (&year, days) in baseline {
+            for (&day, inputs) in days {
+                for (input, phases) in &inputs.0 {
+                    let known = answers
+                        .get(&year)
+                        .and_then(|y| y.get(&day))
+                        .and_then(|d| d.get(input));
+                    for (part, baseline) in (1..).zip(&phases.parts) {
+                        let Some(baseline) = baseline else { continue };
+                        let correct = baseline.answer.as_deref().and_then(|answer| {
+                            known.and_then(|k| k[part as usize - 1].check(answer).correct())
+                        });
+                        let time = baseline.time_stats().map(|t| t.mean);
+                        let status = self.status.entry((year, day, part)).or_insert(PartStatus {
+                            correct: Some(true),
+                            time: None,
+                        });
+                        status.correct = match (status.correct, correct) {
+                            (Some(false), _) | (_, Some(false)) => Some(false),
+                            (Some(true), Some(true)) => Some(true),
+                            _ => None,
+                        };
+                        status.time = status.time.max(time);
+                    }
+                }
+            }
+        }
     }
 }
 impl FromStr for Filters {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(
-            s.split("|").map(str::trim).map(str::parse).try_collect()?,
-        ))
+        Self::parse_with(s, &Presets::new(), None)
     }
 }
 impl Display for Filters {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0
-            .iter()
-            .format(if f.alternate() { " | " } else { "|" })
-            .fmt(f)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expr.fmt_with(f, 0)
     }
 }
 impl Default for Filters {
     fn default() -> Self {
-        Self(BTreeSet::from([Default::default()]))
+        Self {
+            expr: Expr::Parts {
+                years: List::all(),
+                days: List::all(),
+                parts: List::all(),
+            },
+            status: Status::new(),
+        }
+    }
+}
+
+/// A syntax error in a query
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    query: String,
+    /// Position of the error, in characters from 1
+    column: usize,
+    message: String,
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} at column {}", self.message, self.column)?;
+        writeln!(f, "    {}", self.query)?;
+        write!(f, "    {:>1$}", "^", self.column)
+    }
+}
+impl Error for ParseError {}
+
+/// Recursive descent parser of the queries
+struct Parser<'s> {
+    input: &'s str,
+    /// Position in bytes
+    pos: usize,
+    presets: &'s Presets,
+    /// File the presets are read from
+    source: Option<&'s Path>,
+    /// Presets whose query is being parsed, to detect cycles
+    expanding: Vec<&'s str>,
+}
+impl<'s> Parser<'s> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError {
+            query: self.input.to_owned(),
+            column: self.input[..self.pos].chars().count() + 1,
+            message: message.into(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consume the token if it is next
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    /// Consume the characters matching `pred`
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'s str {
+        let start = self.pos;
+        let len = self.rest().find(|c| !pred(c)).unwrap_or(self.rest().len());
+        self.pos += len;
+        &self.input[start..self.pos]
+    }
+
+    /// The whole input
+    fn query(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.union()?;
+        self.skip_whitespace();
+        if let Some(c) = self.rest().chars().next() {
+            return self.error(format!("unexpected `{c}`"));
+        }
+        Ok(expr)
+    }
+
+    fn union(&mut self) -> Result<Expr, ParseError> {
+        let mut exprs = vec![self.intersection()?];
+        while self.eat("|") {
+            exprs.push(self.intersection()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.pop().unwrap()
+        } else {
+            Expr::Or(exprs)
+        })
+    }
+
+    fn intersection(&mut self) -> Result<Expr, ParseError> {
+        let mut exprs = vec![self.unary()?];
+        loop {
+            if self.eat("&") {
+                exprs.push(self.unary()?);
+            } else if self.eat("-") {
+                exprs.push(Expr::Not(Box::new(self.unary()?)));
+            } else {
+                break;
+            }
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.pop().unwrap()
+        } else {
+            Expr::And(exprs)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.union()?;
+                if !self.eat(")") {
+                    return self.error("expected `)`");
+                }
+                Ok(expr)
+            }
+            Some('@') => self.preset(),
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => self.predicate(),
+            Some(c) if c.is_ascii_digit() || c == '.' => self.parts(),
+            Some(c) => self.error(format!(
+                "expected years, a predicate, a preset or `(`, found `{c}`"
+            )),
+            None => self.error("expected years, a predicate, a preset or `(`"),
+        }
+    }
+
+    fn preset(&mut self) -> Result<Expr, ParseError> {
+        let start = self.pos;
+        self.pos += '@'.len_utf8();
+        let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        let Some((name, query)) = self.presets.get_key_value(name) else {
+            self.pos = start;
+            return self.error(match self.source {
+                Some(source) => format!("unknown preset `@{name}` in {}", source.display()),
+                None => format!("unknown preset `@{name}`"),
+            });
+        };
+        if self.expanding.contains(&name.as_str()) {
+            self.pos = start;
+            return self.error(format!("preset `@{name}` refers to itself"));
+        }
+        let mut parser = Parser {
+            input: query,
+            pos: 0,
+            presets: self.presets,
+            source: self.source,
+            expanding: self
+                .expanding
+                .iter()
+                .copied()
+                .chain([name.as_str()])
+                .collect(),
+        };
+        parser.query().map_err(|err| ParseError {
+            message: format!("in preset `@{name}`, {}", err.message),
+            ..err
+        })
+    }
+
+    fn predicate(&mut self) -> Result<Expr, ParseError> {
+        let start = self.pos;
+        let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        Ok(Expr::Predicate(match name {
+            "long_running" => Predicate::LongRunning,
+            "multiline" => Predicate::Multiline,
+            "correct" => Predicate::Correct,
+            "wrong" => Predicate::Wrong,
+            "unknown" => Predicate::Unknown,
+            "slow" => {
+                if !self.eat(">") {
+                    return self.error("expected `>` and a duration after `slow`");
+                }
+                self.skip_whitespace();
+                let start = self.pos;
+                let duration = self.take_while(|c| c.is_alphanumeric() || c == '.');
+                match humantime::parse_duration(duration) {
+                    Ok(duration) => Predicate::Slower(duration),
+                    Err(err) => {
+                        self.pos = start;
+                        return self.error(format!("invalid duration: {err}"));
+                    }
+                }
+            }
+            _ => {
+                let msg = format!("unknown predicate `{name}`");
+                self.pos = start;
+                return self.error(msg);
+            }
+        }))
+    }
+
+    fn parts(&mut self) -> Result<Expr, ParseError> {
+        let years = self.list("year")?;
+        let mut days = List::all();
+        let mut parts = List::all();
+        if self.eat("::") {
+            days = self.list("day")?;
+            if self.eat("::") {
+                let start = self.pos;
+                parts = self.list("part")?;
+                let valid = |p: &u8| *p == 1 || *p == 2;
+                let all_valid = parts.0.iter().all(|range| match range {
+                    Range::All => true,
+                    Range::From(a) | Range::To(a) => valid(a),
+                    Range::Between(a, b) => valid(a) && valid(b),
+                });
+                if !all_valid {
+                    self.pos = start;
+                    self.skip_whitespace();
+                    return self.error("the parts are 1 and 2");
+                }
+            }
+        }
+        Ok(Expr::Parts { years, days, parts })
+    }
+
+    fn list<T: FromStr + Ord + Copy>(&mut self, what: &str) -> Result<List<T>, ParseError> {
+        let mut ranges = vec![self.range(what)?];
+        while self.eat(",") {
+            ranges.push(self.range(what)?);
+        }
+        Ok(List(ranges))
+    }
+
+    fn range<T: FromStr + Ord + Copy>(&mut self, what: &str) -> Result<Range<T>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let first = self.number(what)?;
+        if !self.eat("..") {
+            return match first {
+                Some(value) => Ok(Range::Between(value, value)),
+                None => self.error(format!("expected a {what} or a range")),
+            };
+        }
+        Ok(match (first, self.number(what)?) {
+            (None, None) => Range::All,
+            (Some(a), None) => Range::From(a),
+            (None, Some(b)) => Range::To(b),
+            (Some(a), Some(b)) if a <= b => Range::Between(a, b),
+            (Some(_), Some(_)) => {
+                self.pos = start;
+                return self.error(format!("the {what} range is empty"));
+            }
+        })
+    }
+
+    fn number<T: FromStr>(&mut self, what: &str) -> Result<Option<T>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Ok(None);
+        }
+        match digits.parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => {
+                let msg = format!("invalid {what} {digits}");
+                self.pos = start;
+                self.error(msg)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(query: &str) -> usize {
+        query.parse::<Filters>().unwrap_err().column
+    }
+
+    #[test]
+    fn ranges() {
+        let filters: Filters = "2016..::..3,10::2".parse().unwrap();
+        assert!(filters.accept_year(2016));
+        assert!(filters.accept_year(2024));
+        assert!(!filters.accept_year(2015));
+        assert!(filters.accept_day(2020, 2));
+        assert!(filters.accept_day(2020, 10));
+        assert!(!filters.accept_day(2020, 4));
+        assert!(filters.accept_part(2020, 3, 2, None));
+        assert!(!filters.accept_part(2020, 3, 1, None));
+        assert_eq!(filters.to_string(), "2016..::..3,10::2");
+    }
+
+    #[test]
+    fn precedence() {
+        // `-` binds tighter than `|`
+        let filters: Filters = "2015 | 2016 - 2016::1".parse().unwrap();
+        assert!(filters.accept_day(2015, 1));
+        assert!(filters.accept_day(2016, 2));
+        assert!(!filters.accept_day(2016, 1));
+        assert_eq!(filters.to_string(), "2015|2016-2016::1");
+
+        let filters: Filters = "(2015 | 2016) & !2016::1".parse().unwrap();
+        assert!(filters.accept_day(2015, 1));
+        assert!(!filters.accept_day(2016, 1));
+        assert!(!filters.accept_year(2017));
+        assert_eq!(filters.to_string(), "(2015|2016)-2016::1");
+    }
+
+    #[test]
+    fn exclusion() {
+        let filters: Filters = "2022 - 2022::19 - 2022::20::2".parse().unwrap();
+        assert!(filters.accept_year(2022));
+        assert!(!filters.accept_day(2022, 19));
+        assert!(filters.accept_day(2022, 20));
+        assert!(filters.accept_part(2022, 20, 1, None));
+        assert!(!filters.accept_part(2022, 20, 2, None));
+    }
+
+    #[test]
+    fn predicates() {
+        let meta = PartMeta {
+            multiline: true,
+            long_running: false,
+        };
+        let filters: Filters = "multiline".parse().unwrap();
+        assert!(filters.accept_part(2015, 1, 1, Some(meta)));
+        // not known yet
+        assert!(filters.accept_day(2015, 1));
+        let filters: Filters = "long_running".parse().unwrap();
+        assert!(!filters.accept_part(2015, 1, 1, Some(meta)));
+
+        let mut status = Status::new();
+        let time = Some(Duration::from_millis(200));
+        status.insert(
+            (2015, 1, 1),
+            PartStatus {
+                correct: Some(true),
+                time,
+            },
+        );
+        status.insert(
+            (2015, 1, 2),
+            PartStatus {
+                correct: Some(false),
+                time: None,
+            },
+        );
+        let check = |query: &str, part| {
+            let mut filters: Filters = query.parse().unwrap();
+            filters.status = status.clone();
+            filters.accept_part(2015, 1, part, None)
+        };
+        assert!(check("correct", 1));
+        assert!(!check("correct", 2));
+        assert!(check("wrong", 2));
+        assert!(!check("unknown", 1));
+        assert!(!check("unknown", 2));
+        assert!(check("slow>100ms", 1));
+        assert!(!check("slow > 1s", 1));
+        assert!(!check("slow>100ms", 2));
+    }
+
+    #[test]
+    fn presets() {
+        let presets = Presets::from([
+            ("quick".to_owned(), "2015 - slow>1s".to_owned()),
+            ("loop".to_owned(), "@loop | 2016".to_owned()),
+        ]);
+        let filters = Filters::parse_with("@quick - 2015::3", &presets, None).unwrap();
+        assert!(filters.accept_day(2015, 1));
+        assert!(!filters.accept_day(2015, 3));
+        assert!(!filters.accept_year(2016));
+
+        let err = Filters::parse_with("2015 | @loop", &presets, None).unwrap_err();
+        assert_eq!(err.column, 1);
+        assert!(err.message.contains("refers to itself"));
+        let err = Filters::parse_with("2015 | @missing", &presets, None).unwrap_err();
+        assert_eq!(err.column, 8);
+        let err = Filters::parse_with("@missing", &presets, Some(Path::new("aoc-runner.toml")))
+            .unwrap_err();
+        assert_eq!(err.message, "unknown preset `@missing` in aoc-runner.toml");
+    }
+
+    #[test]
+    fn error_columns() {
+        assert_eq!(column("2015::(1,2"), 7);
+        assert_eq!(column("status=bogus"), 1);
+        assert_eq!(column("2015 & slow>fast"), 13);
+        assert_eq!(column("2015::3..1"), 7);
+        assert_eq!(column("2015::1:: 3"), 11);
+        assert_eq!(column("(2015 | 2016"), 13);
+        assert_eq!(column("2015 2016"), 6);
+        assert_eq!(column("2015 |"), 7);
     }
 }
@@ -0,0 +1,278 @@
+//! Append-only history of the runs
+//!
+//! The history is a JSON-lines file, each line recording the results of a run together with
+//! where and when it happened.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    num::NonZeroUsize,
+    path::Path,
+    process::Command,
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::{Baseline, Baselines, InputBaseline, PerInput, Phase};
+
+/// Maximum number of past runs shown in the trends
+pub(crate) const TREND_LEN: usize = 20;
+
+/// A recorded run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Run<R> {
+    /// When the run happened, in RFC 3339 format
+    pub(crate) timestamp: String,
+    /// Commit checked out during the run, if in a git repository
+    pub(crate) commit: Option<String>,
+    pub(crate) host: Option<String>,
+    /// Version of the compiler that built the solutions
+    pub(crate) rustc: String,
+    /// Query selecting the problems of the run
+    pub(crate) filters: String,
+    /// Results of the run, in the same format as the baselines
+    pub(crate) results: R,
+}
+impl<R> Run<R> {
+    /// Record a run happening now
+    pub(crate) fn now(filters: String, results: R) -> Self {
+        Self {
+            timestamp: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            commit: git_commit(),
+            host: host_name(),
+            rustc: env!("RUNNER_RUSTC_VERSION").to_owned(),
+            filters,
+            results,
+        }
+    }
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8(output.stdout).ok())
+        .flatten()
+        .map(|s| s.trim().to_owned())
+}
+
+fn host_name() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| {
+            let output = Command::new("hostname").output().ok()?;
+            String::from_utf8(output.stdout).ok()
+        })
+        .map(|s| s.trim().to_owned())
+        .filter(|s| !s.is_empty())
+}
+
+/// Append a run to the history
+pub(crate) fn append<R: Serialize>(file: impl AsRef<Path>, run: &Run<R>) -> anyhow::Result<()> {
+    let mut line = serde_json::to_vec(run).context("Cannot serialize the run")?;
+    line.push(b'\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file)
+        .context("Cannot open history file")?
+        .write_all(&line)
+        .context("Cannot write history file")
+}
+
+/// All the recorded runs, oldest first
+#[derive(Debug, Clone, Default)]
+pub(crate) struct History(Vec<Run<Baselines>>);
+impl History {
+    pub(crate) fn read(file: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = File::open(file).context("Cannot open history file")?;
+        let mut runs = vec![];
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.context("Cannot read history file")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            runs.push(
+                serde_json::from_str(&line)
+                    .with_context(|| format!("Cannot parse run at line {}", i + 1))?,
+            );
+        }
+        Ok(Self(runs))
+    }
+
+    /// Build a baseline from the recorded runs
+    pub(crate) fn baseline(&self, selector: &Selector) -> anyhow::Result<Baselines> {
+        let mut selected = PartialBaselines::new();
+        match selector {
+            Selector::Last(n) => {
+                // pooling the statistics of the last `n` runs of each part
+                for (key, baseline) in self.parts().rev() {
+                    let (pooled, count) = part_entry(&mut selected, key);
+                    if *count >= n.get() {
+                        continue;
+                    }
+                    *count += 1;
+                    let Some(pooled) = pooled else {
+                        *pooled = Some(baseline.clone());
+                        continue;
+                    };
+                    pooled.timing = match (pooled.time_stats(), baseline.time_stats()) {
+                        (Some(a), Some(b)) => Some(a.merge(&b)),
+                        (a, b) => a.or(b),
+                    };
+                    pooled.time = pooled.timing.map(|t| t.mean);
+                }
+            }
+            Selector::Commit(rev) => {
+                let Some(run) = self
+                    .0
+                    .iter()
+                    .rev()
+                    .find(|run| run.commit.as_ref().is_some_and(|c| c.starts_with(rev)))
+                else {
+                    bail!("No recorded run at commit {rev}")
+                };
+                return Ok(run.results.clone());
+            }
+            Selector::Best => {
+                for (key, baseline) in self.parts() {
+                    let Some(mean) = baseline.time_stats().map(|t| t.mean) else {
+                        continue;
+                    };
+                    let (best, _) = part_entry(&mut selected, key);
+                    if best
+                        .as_ref()
+                        .and_then(Baseline::time_stats)
+                        .is_none_or(|best| mean < best.mean)
+                    {
+                        *best = Some(baseline.clone())
+                    }
+                }
+            }
+        }
+        Ok(selected
+            .into_iter()
+            .map(|(y, days)| {
+                (
+                    y,
+                    days.into_iter()
+                        .map(|(d, inputs)| {
+                            let inputs = inputs
+                                .into_iter()
+                                .map(|(name, phases)| {
+                                    let mut baseline = InputBaseline::default();
+                                    for (phase, (b, _)) in phases {
+                                        *baseline.phase_mut(phase) = b;
+                                    }
+                                    (name, baseline)
+                                })
+                                .collect();
+                            (d, PerInput(inputs))
+                        })
+                        .collect(),
+                )
+            })
+            .collect())
+    }
+
+    /// Mean running times of the last runs of each part, oldest first
+    pub(crate) fn trends(&self) -> Trends {
+        let mut trends = Trends::new();
+        for ((y, d, input, phase), baseline) in self.parts() {
+            if let Some(time) = baseline.time_stats() {
+                trends
+                    .entry((y, d, input.to_owned(), phase))
+                    .or_default()
+                    .push(time.mean)
+            }
+        }
+        for trend in trends.values_mut() {
+            let skip = trend.len().saturating_sub(TREND_LEN);
+            trend.drain(..skip);
+        }
+        trends
+    }
+
+    /// All the recorded parts and parsing steps, oldest first
+    fn parts(&self) -> impl DoubleEndedIterator<Item = ((u16, u8, &str, Phase), &Baseline)> {
+        self.0.iter().flat_map(|run| {
+            run.results.iter().flat_map(|(y, days)| {
+                days.iter().flat_map(move |(d, inputs)| {
+                    inputs.0.iter().flat_map(move |(name, phases)| {
+                        phases
+                            .phases()
+                            .map(move |(phase, b)| ((*y, *d, name.as_str(), phase), b))
+                    })
+                })
+            })
+        })
+    }
+}
+
+/// Baselines being selected, with the number of runs selected for each part
+type PartialBaselines =
+    BTreeMap<u16, BTreeMap<u8, BTreeMap<String, BTreeMap<Phase, (Option<Baseline>, usize)>>>>;
+
+fn part_entry<'a>(
+    baselines: &'a mut PartialBaselines,
+    (y, d, input, phase): (u16, u8, &str, Phase),
+) -> (&'a mut Option<Baseline>, &'a mut usize) {
+    let (b, c) = baselines
+        .entry(y)
+        .or_default()
+        .entry(d)
+        .or_default()
+        .entry(input.to_owned())
+        .or_default()
+        .entry(phase)
+        .or_default();
+    (b, c)
+}
+
+/// Recent running times of each part and parsing step on each input
+pub(crate) type Trends = BTreeMap<(u16, u8, String, Phase), Vec<Duration>>;
+
+/// Selection of the recorded runs to compare against
+#[derive(Debug, Clone)]
+pub(crate) enum Selector {
+    /// The last `n` runs of each part, pooled together
+    Last(NonZeroUsize),
+    /// The last run at a given commit
+    Commit(String),
+    /// The fastest run of each part
+    Best,
+}
+impl Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Selector::Last(n) => write!(f, "last:{n}"),
+            Selector::Commit(rev) => write!(f, "commit:{rev}"),
+            Selector::Best => write!(f, "best"),
+        }
+    }
+}
+impl FromStr for Selector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().split_once(':') {
+            None if s.trim() == "last" => Self::Last(NonZeroUsize::MIN),
+            None if s.trim() == "best" => Self::Best,
+            Some(("last", n)) => Self::Last(n.trim().parse().context("Invalid number of runs")?),
+            Some(("commit", rev)) if !rev.trim().is_empty() => Self::Commit(rev.trim().to_owned()),
+            _ => {
+                bail!("Unrecognized history selector, expected `last[:N]`, `commit:REV` or `best`")
+            }
+        })
+    }
+}
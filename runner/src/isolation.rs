@@ -0,0 +1,340 @@
+//! Running parts in a child process
+//!
+//! The child is the runner binary itself, started with the hidden `--isolated-worker` flag and a
+//! filter selecting a single part, or a single day together with `--isolated-parse` to measure
+//! its parsing step. It reads the input from stdin, preceded by its length on its own line, and
+//! writes the JSON-serialized result of the measurement on stdout. The plugins are loaded again by
+//! the child.
+//!
+//! Before timing the part the child writes a `timing` line on stdout, and waits for a newline on
+//! stdin, sent once the runner holds the timing lock. The lock is released when the result arrives.
+//!
+//! Only the runner writes on the stdout of the child: on unix, what the solutions print goes to its
+//! stderr, forwarded with the logs.
+
+use std::{
+    env::current_exe,
+    ffi::OsString,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::PathBuf,
+    process::{Child, ChildStdin, ChildStdout, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+
+use crate::{bench::BenchSettings, FailedMeasurements, Library, Measurements, Part, Phase};
+
+/// How often the child is polled for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Line written by the child when it is ready to be timed
+const TIMING: &[u8] = b"timing\n";
+
+/// Settings deciding which parts run in a child process
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Isolation<'p> {
+    /// Run all parts in a child process
+    pub(crate) forced: bool,
+    /// Default timeout for all the parts
+    pub(crate) timeout: Option<Duration>,
+    /// Plugins to load in the child
+    pub(crate) plugins: &'p [PathBuf],
+}
+impl Isolation<'_> {
+    /// Check if a part must be isolated, and with what timeout
+    ///
+    /// A part with a timeout is always isolated, as the timeout cannot be enforced in process.
+    pub(crate) fn for_part(&self, part: &Part) -> Option<Option<Duration>> {
+        let timeout = part.timeout.or(self.timeout);
+        (self.forced || timeout.is_some()).then_some(timeout)
+    }
+}
+
+/// Measure a part in a child process
+///
+/// The timing lock is only held while the child is timed, and the time spent waiting for it does
+/// not count for the timeout.
+pub(crate) fn measure(
+    (year, day, phase): (u16, u8, Phase),
+    example: Option<&str>,
+    bench: Option<BenchSettings>,
+    input: &str,
+    timeout: Option<Duration>,
+    plugins: &[PathBuf],
+    timing_lock: &Mutex<()>,
+) -> Result<Measurements, FailedMeasurements> {
+    let mut child = current_exe()
+        .and_then(|exe| {
+            let mut command = Command::new(exe);
+            match phase {
+                Phase::Parse => command
+                    .arg(format!("{year}::{day}"))
+                    .arg("--isolated-parse"),
+                Phase::Part(part) => command.arg(format!("{year}::{day}::{part}")),
+            };
+            command
+                .arg("--isolated-worker")
+                .args(example.map(|name| format!("--isolated-example={name}")))
+                .args(plugins.iter().map(|plugin| {
+                    let mut arg = OsString::from("--plugins=");
+                    arg.push(plugin);
+                    arg
+                }))
+                .args(bench.map_or_else(
+                    || vec!["--repeats".to_owned(), "0".to_owned()],
+                    |bench| bench.to_args(),
+                ))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+        })
+        .map_err(|err| FailedMeasurements::Spawn(err.to_string()))?;
+
+    let lock_wait = LockWait::default();
+    let (status, stdout, stderr) = thread::scope(|s| {
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let mut stderr = child.stderr.take().unwrap();
+        let lock_wait = &lock_wait;
+        let stdout = s.spawn(move || talk(stdin, stdout, input, timing_lock, lock_wait));
+        let stderr = s.spawn(move || {
+            let mut buf = vec![];
+            stderr.read_to_end(&mut buf).map(|_| buf)
+        });
+
+        let status = wait(&mut child, timeout, lock_wait);
+        (
+            status,
+            stdout.join().unwrap().unwrap_or_default(),
+            stderr.join().unwrap().unwrap_or_default(),
+        )
+    });
+    // forwarding the logs and panic messages of the child, unless logging is off as in the TUI
+    if log::max_level() != log::LevelFilter::Off {
+        let _ = io::stderr().write_all(&stderr);
+    }
+
+    let (status, timed_out) = match status {
+        Ok(exit) => exit,
+        Err(err) => return Err(FailedMeasurements::Spawn(err.to_string())),
+    };
+    // the child can finish just before being killed
+    if status.success() {
+        if let Ok(res) = serde_json::from_slice(&stdout) {
+            return res;
+        }
+    }
+    let timeout = timeout.filter(|_| timed_out);
+    Err(classify_failure(
+        status,
+        timeout,
+        &String::from_utf8_lossy(&stderr),
+    ))
+}
+
+/// Time the child spent waiting for the timing lock, that does not count for the timeout
+#[derive(Debug, Default)]
+struct LockWait {
+    /// The child is waiting right now
+    waiting: AtomicBool,
+    /// Nanoseconds waited before
+    waited: AtomicU64,
+}
+
+/// Send the input to the child, and hold the timing lock while it is timed
+///
+/// Return the output of the child after the timing, that is its result.
+fn talk(
+    mut stdin: ChildStdin,
+    stdout: ChildStdout,
+    input: &str,
+    timing_lock: &Mutex<()>,
+    lock_wait: &LockWait,
+) -> io::Result<Vec<u8>> {
+    // the child might not read all the input if it crashes, so errors are ignored
+    let _ = writeln!(stdin, "{}", input.len())
+        .and_then(|()| stdin.write_all(input.as_bytes()))
+        .and_then(|()| stdin.flush());
+    let mut stdout = BufReader::new(stdout);
+    let mut output = vec![];
+    stdout.read_until(b'\n', &mut output)?;
+    let _guard = if output == TIMING {
+        output.clear();
+        lock_wait.waiting.store(true, Ordering::Relaxed);
+        let start = Instant::now();
+        let guard = timing_lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let waited = start.elapsed().as_nanos() as u64;
+        lock_wait.waited.fetch_add(waited, Ordering::Relaxed);
+        lock_wait.waiting.store(false, Ordering::Relaxed);
+        let _ = stdin.write_all(b"\n").and_then(|()| stdin.flush());
+        Some(guard)
+    } else {
+        None
+    };
+    stdout.read_to_end(&mut output)?;
+    Ok(output)
+}
+
+/// Wait for the child, killing it if it goes over the timeout
+///
+/// Return its exit status, and if it was killed.
+fn wait(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    lock_wait: &LockWait,
+) -> io::Result<(ExitStatus, bool)> {
+    let Some(timeout) = timeout else {
+        return child.wait().map(|status| (status, false));
+    };
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok((status, false));
+        }
+        let waited = Duration::from_nanos(lock_wait.waited.load(Ordering::Relaxed));
+        if !lock_wait.waiting.load(Ordering::Relaxed) && start.elapsed() >= timeout + waited {
+            child.kill()?;
+            return Ok((child.wait()?, true));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Find out why a child did not report a result
+///
+/// `timeout` is given if the child was killed for going over it.
+fn classify_failure(
+    status: ExitStatus,
+    timeout: Option<Duration>,
+    stderr: &str,
+) -> FailedMeasurements {
+    if stderr.contains("has overflowed its stack") {
+        return FailedMeasurements::StackOverflow;
+    }
+    if stderr.contains("memory allocation of") {
+        return FailedMeasurements::OutOfMemory;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        const SIGABRT: i32 = 6;
+        const SIGKILL: i32 = 9;
+
+        match (status.signal(), timeout) {
+            (Some(SIGABRT), _) => return FailedMeasurements::Aborted,
+            (Some(SIGKILL), Some(timeout)) => return FailedMeasurements::Timeout(timeout),
+            // we did not kill it, so the most likely culprit is the OOM killer
+            (Some(SIGKILL), None) => return FailedMeasurements::OutOfMemory,
+            (Some(signal), _) => return FailedMeasurements::Signal(signal),
+            (None, _) => (),
+        }
+    }
+    match timeout {
+        Some(timeout) => FailedMeasurements::Timeout(timeout),
+        None => FailedMeasurements::Exited(status.code()),
+    }
+}
+
+/// Entry point of the child process
+///
+/// The library must contain exactly one part, that is measured on the input given on stdin. If
+/// an example is given, the part is the one run on that example. If `parse` is set, the library
+/// must instead contain exactly one day, whose parsing step is measured.
+pub(crate) fn worker(
+    library: &Library,
+    bench: Option<BenchSettings>,
+    example: Option<&str>,
+    parse: bool,
+) -> anyhow::Result<()> {
+    let part = if parse {
+        let mut days = library
+            .solutions
+            .values()
+            .flat_map(|y| y.solutions.values());
+        let (Some(day), None) = (days.next(), days.next()) else {
+            bail!("The filter given to the isolated worker must select a single day")
+        };
+        day.parse
+            .clone()
+            .context("The selected day has no parsing step")?
+    } else {
+        select_part(library, example)?
+    };
+
+    let input = read_input().context("Cannot read the input from stdin")?;
+    let output = take_stdout().context("Cannot redirect the output of the solutions")?;
+
+    let res = part.measure_with(bench, &input, || {
+        // the runner answers once it holds the timing lock
+        let _ = (&output).write_all(TIMING).and_then(|()| (&output).flush());
+        let _ = io::stdin().read_exact(&mut [0]);
+    });
+
+    serde_json::to_writer(&output, &res).context("Cannot write the result")?;
+    (&output).flush().context("Cannot write the result")?;
+    Ok(())
+}
+
+/// Take the stdout of the child for the runner, sending what the solutions print to stderr
+#[cfg(unix)]
+fn take_stdout() -> io::Result<std::fs::File> {
+    use std::{fs::File, os::fd::AsFd};
+
+    let stdout = io::stdout().lock();
+    let output = File::from(stdout.as_fd().try_clone_to_owned()?);
+    // SAFETY: both descriptors stay open, and the lock keeps anyone from writing meanwhile
+    if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(output)
+}
+
+/// Take the stdout of the child for the runner, that the solutions must not print on
+#[cfg(not(unix))]
+fn take_stdout() -> io::Result<io::Stdout> {
+    Ok(io::stdout())
+}
+
+/// Read the input sent by the runner, preceded by its length
+fn read_input() -> io::Result<String> {
+    let mut stdin = io::stdin().lock();
+    let mut len = String::new();
+    stdin.read_line(&mut len)?;
+    let len =
+        (len.trim().parse()).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut input = vec![0; len];
+    stdin.read_exact(&mut input)?;
+    String::from_utf8(input).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// The only part in the library, or the one run on the example
+fn select_part(library: &Library, example: Option<&str>) -> anyhow::Result<Part> {
+    let mut parts = library
+        .solutions
+        .values()
+        .flat_map(|y| y.solutions.values())
+        .flat_map(|d| (0..2).filter_map(move |p| Some((d, p, d.parts[p].clone()?))));
+    let Some((day, p, part)) = parts.next() else {
+        bail!("The filter given to the isolated worker selected no part")
+    };
+    if parts.next().is_some() {
+        bail!("The filter given to the isolated worker selected multiple parts")
+    }
+    match example {
+        Some(name) => day
+            .examples
+            .iter()
+            .find(|e| e.name == name)
+            .and_then(|e| e.part(day, p))
+            .with_context(|| format!("The selected part is not run on example {name}")),
+        None => Ok(part),
+    }
+}
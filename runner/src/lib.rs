@@ -1,279 +1,105 @@
-#![feature(iterator_try_collect)]
 use std::{
-    borrow::Cow,
+    borrow::{Borrow, Cow},
     collections::BTreeMap,
+    env,
     ffi::OsString,
     fmt::{self, Debug, Display, Write},
-    fs::{read_to_string, File},
-    hint::black_box,
-    io,
-    num::NonZeroU32,
-    panic::catch_unwind,
+    fs::{self, File},
+    io::{self, IsTerminal, Write as _},
+    num::{NonZeroU32, NonZeroUsize},
+    panic::RefUnwindSafe,
     path::{Path, PathBuf},
     process::exit,
     rc::Rc,
-    time::{Duration, Instant},
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::Duration,
 };
 
-use anyhow::Context;
-use clap::Parser;
+use anyhow::{bail, Context};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use html_builder::{Buffer, Html5, Node};
 use itertools::Itertools;
 use report::Report;
-use serde::{Deserialize, Serialize, Serializer};
-
-use crate::filters::Filters;
-
-#[derive(Clone, Debug, Copy)]
-pub enum PartFn {
-    ISize(fn(&str) -> isize),
-    I64(fn(&str) -> i64),
-    I32(fn(&str) -> i32),
-    I16(fn(&str) -> i16),
-    I8(fn(&str) -> i8),
-    USize(fn(&str) -> usize),
-    U64(fn(&str) -> u64),
-    U32(fn(&str) -> u32),
-    U16(fn(&str) -> u16),
-    U8(fn(&str) -> u8),
-    String(fn(&str) -> String),
-    Str(fn(&str) -> &str),
-    Bites8(fn(&str) -> [u8; 8]),
-}
-impl PartFn {
-    fn call(&self, input: &str) -> String {
-        match self {
-            PartFn::ISize(f) => (f)(input).to_string(),
-            PartFn::I64(f) => (f)(input).to_string(),
-            PartFn::I32(f) => (f)(input).to_string(),
-            PartFn::I16(f) => (f)(input).to_string(),
-            PartFn::I8(f) => (f)(input).to_string(),
-            PartFn::USize(f) => (f)(input).to_string(),
-            PartFn::U64(f) => (f)(input).to_string(),
-            PartFn::U32(f) => (f)(input).to_string(),
-            PartFn::U16(f) => (f)(input).to_string(),
-            PartFn::U8(f) => (f)(input).to_string(),
-            PartFn::String(f) => (f)(input),
-            PartFn::Str(f) => (f)(input).to_string(),
-            PartFn::Bites8(f) => String::from_utf8(Vec::from(f(input)))
-                .expect("The solution must return valid utf-8"),
-        }
-    }
-    fn time(&self, input: &str, reps: u32) -> Duration {
-        (match self {
-            PartFn::ISize(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::I64(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::I32(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::I16(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::I8(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::USize(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::U64(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::U32(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::U16(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::U8(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::String(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::Str(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-            PartFn::Bites8(f) => {
-                let s = Instant::now();
-                for _ in 0..reps {
-                    black_box((f)(black_box(input)));
-                }
-                s.elapsed()
-            }
-        }) / reps
-    }
-}
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-impl From<fn(&str) -> isize> for PartFn {
-    fn from(value: fn(&str) -> isize) -> Self {
-        PartFn::ISize(value)
-    }
-}
-impl From<fn(&str) -> i64> for PartFn {
-    fn from(value: fn(&str) -> i64) -> Self {
-        PartFn::I64(value)
-    }
-}
-impl From<fn(&str) -> i32> for PartFn {
-    fn from(value: fn(&str) -> i32) -> Self {
-        PartFn::I32(value)
-    }
-}
-impl From<fn(&str) -> i16> for PartFn {
-    fn from(value: fn(&str) -> i16) -> Self {
-        PartFn::I16(value)
-    }
-}
-impl From<fn(&str) -> i8> for PartFn {
-    fn from(value: fn(&str) -> i8) -> Self {
-        PartFn::I8(value)
-    }
-}
-impl From<fn(&str) -> usize> for PartFn {
-    fn from(value: fn(&str) -> usize) -> Self {
-        PartFn::USize(value)
-    }
-}
-impl From<fn(&str) -> u64> for PartFn {
-    fn from(value: fn(&str) -> u64) -> Self {
-        PartFn::U64(value)
-    }
-}
-impl From<fn(&str) -> u32> for PartFn {
-    fn from(value: fn(&str) -> u32) -> Self {
-        PartFn::U32(value)
-    }
-}
-impl From<fn(&str) -> u16> for PartFn {
-    fn from(value: fn(&str) -> u16) -> Self {
-        PartFn::U16(value)
-    }
-}
-impl From<fn(&str) -> u8> for PartFn {
-    fn from(value: fn(&str) -> u8) -> Self {
-        PartFn::U8(value)
-    }
-}
-impl From<fn(&str) -> String> for PartFn {
-    fn from(value: fn(&str) -> String) -> Self {
-        PartFn::String(value)
-    }
-}
-impl From<fn(&str) -> &str> for PartFn {
-    fn from(value: fn(&str) -> &str) -> Self {
-        PartFn::Str(value)
-    }
-}
-impl From<fn(&str) -> [u8; 8]> for PartFn {
-    fn from(value: fn(&str) -> [u8; 8]) -> Self {
-        PartFn::Bites8(value)
-    }
-}
+pub use crate::{
+    answer::{Answer, Displayed, Outcome, PartFn},
+    params::{ParamError, Params},
+};
+use crate::{
+    answer::{ParsedCache, ParsedInputs},
+    answers::Answers,
+    bench::{BenchSettings, TimeStats},
+    filters::{Filters, PartMeta},
+    history::{History, Trends},
+    isolation::Isolation,
+    memory::MemoryStats,
+};
+pub use discovery_macro::export_plugin;
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug)]
 pub struct Part {
     fun: PartFn,
     multiline: bool,
     long_running: bool,
+    timeout: Option<Duration>,
+    params: Params,
 }
 impl Part {
     fn measure(
         &self,
-        repeats: Option<NonZeroU32>,
+        bench: Option<BenchSettings>,
         input: &str,
+        timing_lock: &Mutex<()>,
     ) -> Result<Measurements, FailedMeasurements> {
-        catch_unwind(|| {
+        self.measure_with(bench, input, || {
+            timing_lock.lock().unwrap_or_else(PoisonError::into_inner)
+        })
+    }
+
+    /// Measure the part, holding the guard returned by `lock` during the timed runs
+    fn measure_with<G>(
+        &self,
+        bench: Option<BenchSettings>,
+        input: &str,
+        lock: impl Fn() -> G + RefUnwindSafe,
+    ) -> Result<Measurements, FailedMeasurements> {
+        panics::catch(|| {
             // first run it once to find the answer
             if !self.long_running {
-                let answer = self.fun.call(input);
-                let time = if let Some(reps) = repeats {
-                    Some(self.fun.time(input, reps.get()))
-                } else {
-                    None
-                };
-                Measurements {
+                let (answer, first_run, memory) = self.fun.call(input, &self.params);
+                let answer = answer.map_err(|chain| FailedMeasurements::Error { chain })?;
+                let time = bench.map(|bench| {
+                    let _guard = lock();
+                    bench.measure(|iters| self.fun.run(input, &self.params, iters), first_run)
+                });
+                Ok(Measurements {
                     answer,
                     time,
+                    memory,
                     multiline: self.multiline,
-                }
+                })
             } else {
                 log::info!("Checking long running solution");
-                let s = Instant::now();
-                let answer = self.fun.call(input);
-                let time = if repeats.is_some() {
-                    Some(s.elapsed())
-                } else {
-                    None
-                };
-                Measurements {
-                    answer,
+                // the only run is also the timed one
+                let _guard = bench.map(|_| lock());
+                let (answer, elapsed, memory) = self.fun.call(input, &self.params);
+                let time = bench.map(|_| TimeStats::single(elapsed));
+                Ok(Measurements {
+                    answer: answer.map_err(|chain| FailedMeasurements::Error { chain })?,
                     time,
+                    memory,
                     multiline: self.multiline,
-                }
+                })
             }
         })
-        .map_err(|err| match err.downcast::<String>() {
-            Ok(s) => FailedMeasurements {
-                err: Some(Cow::Owned(*s)),
-            },
-            Err(err) => match err.downcast::<&'static str>() {
-                Ok(s) => FailedMeasurements {
-                    err: Some(Cow::Borrowed(*s)),
-                },
-                Err(_) => FailedMeasurements { err: None },
-            },
+        .unwrap_or_else(|panic| {
+            Err(FailedMeasurements::Panic {
+                err: panic.message,
+                location: panic.location,
+                backtrace: panic.backtrace,
+            })
         })
     }
 }
@@ -282,8 +108,15 @@ impl Part {
 
 pub struct Day {
     day: (u16, u8),
+    /// Parsing step shared by the parts, measured on its own
+    parse: Option<Part>,
+    /// Inputs parsed for the parts and the examples, cleared after each run
+    parsed: Vec<Arc<dyn ParsedCache>>,
     parts: [Option<Part>; 2],
+    examples: Vec<Example>,
     filters: Rc<Filters>,
+    /// Directory of the crate of the solution, searched for an `input.txt`
+    source_dir: Option<PathBuf>,
 }
 impl Day {
     pub fn add_part_1(
@@ -292,11 +125,20 @@ impl Day {
         multiline: bool,
         long_running: bool,
     ) -> &mut Self {
-        if self.filters.accept_part(self.day.0, self.day.1, 1) {
+        let meta = PartMeta {
+            multiline,
+            long_running,
+        };
+        if self
+            .filters
+            .accept_part(self.day.0, self.day.1, 1, Some(meta))
+        {
             self.parts[0] = Some(Part {
                 fun: fun.into(),
                 multiline,
                 long_running,
+                timeout: None,
+                params: Params::default(),
             });
         }
         self
@@ -307,36 +149,319 @@ impl Day {
         multiline: bool,
         long_running: bool,
     ) -> &mut Self {
-        if self.filters.accept_part(self.day.0, self.day.1, 2) {
+        let meta = PartMeta {
+            multiline,
+            long_running,
+        };
+        if self
+            .filters
+            .accept_part(self.day.0, self.day.1, 2, Some(meta))
+        {
             self.parts[1] = Some(Part {
                 fun: fun.into(),
                 multiline,
                 long_running,
+                timeout: None,
+                params: Params::default(),
             });
         }
         self
     }
 
-    fn measure(
-        &self,
-        repeats: Option<NonZeroU32>,
-        inputs: &Path,
-    ) -> Result<[Option<Result<Measurements, FailedMeasurements>>; 2], io::Error> {
-        if self.parts.iter().all(|p| p.is_none()) {
-            return Ok([None, None]);
+    /// Add a parsing step shared by the two parts
+    ///
+    /// The parts added by `build` work on the parsed input. The parsing step is timed on its own,
+    /// and the timings of the parts do not include it.
+    ///
+    /// Each input is parsed once for both parts, so the parsed input must be [`Send`] and [`Sync`].
+    pub fn add_parse<P, F>(&mut self, parse: fn(&str) -> P, build: F) -> &mut Self
+    where
+        P: Send + Sync + 'static,
+        F: FnOnce(&mut Parsed<'_, P>),
+    {
+        self.parse = Some(Part {
+            fun: PartFn::parse(parse),
+            multiline: false,
+            long_running: false,
+            timeout: None,
+            params: Params::default(),
+        });
+        let parsed = Arc::new(ParsedInputs::new(parse));
+        self.parsed.push(parsed.clone());
+        build(&mut Parsed { day: self, parsed });
+        self
+    }
+
+    /// Set a timeout for a part
+    ///
+    /// Parts with a timeout are always run in a child process.
+    pub fn set_part_timeout(&mut self, part: u8, timeout: Duration) -> &mut Self {
+        assert!(part == 1 || part == 2, "Part {part} is invalid");
+        if let Some(part) = &mut self.parts[part as usize - 1] {
+            part.timeout = Some(timeout)
         }
-        log::info!("Measuring day {}", self.day.1);
-        let input = read_to_string(
-            inputs
-                .join(self.day.0.to_string())
-                .join(self.day.1.to_string()),
-        )?;
-        Ok(self.parts.map(|p| p.map(|p| p.measure(repeats, &input))))
+        self
+    }
+
+    /// Set a parameter of a part, to the value used on the inputs
+    ///
+    /// Examples can override it. The part must take [`Params`] to read it. The values are checked
+    /// once the library is built, before running anything.
+    pub fn set_part_param(&mut self, part: u8, name: &str, value: impl Display) -> &mut Self {
+        assert!(part == 1 || part == 2, "Part {part} is invalid");
+        if let Some(part) = &mut self.parts[part as usize - 1] {
+            part.params.set(name, value.to_string())
+        }
+        self
+    }
+
+    /// Set the directory of the crate of the solution
+    ///
+    /// An `input.txt` in it is used when the inputs directory has no input for the day.
+    pub fn set_source_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.source_dir = Some(dir.into());
+        self
+    }
+
+    /// Add an example input
+    ///
+    /// Only the parts with an expected answer are run on the example.
+    pub fn add_example<F>(&mut self, name: &str, input: &'static str, build: F) -> &mut Self
+    where
+        F: FnOnce(&mut Example),
+    {
+        let mut example = Example {
+            name: name.to_owned(),
+            input: Cow::Borrowed(input),
+            answers: [None, None],
+            funs: [None, None],
+            params: Params::default(),
+        };
+        build(&mut example);
+        // the functions run on the example can parse it on their own
+        let parsed = example
+            .funs
+            .iter()
+            .flatten()
+            .filter_map(PartFn::parsed_inputs);
+        self.parsed.extend(parsed);
+        self.examples.push(example);
+        self
+    }
+
+    /// Where the input of the day can be, in order of preference
+    ///
+    /// In the inputs directory the day can be zero-padded, and followed by `.txt`.
+    fn input_paths(&self, inputs: &Path) -> Vec<PathBuf> {
+        let (year, day) = self.day;
+        let year = inputs.join(year.to_string());
+        let mut names = vec![day.to_string()];
+        if day < 10 {
+            names.push(format!("{day:02}"));
+        }
+        let files = names.iter().map(|name| format!("{name}.txt"));
+        let mut paths: Vec<_> = names
+            .iter()
+            .cloned()
+            .chain(files)
+            .map(|name| year.join(name))
+            .collect();
+        paths.extend(self.source_dir.as_ref().map(|dir| dir.join("input.txt")));
+        paths
+    }
+
+    /// Read the inputs of the day, by name
+    ///
+    /// The first of the input paths that exists can either be a file, that is the default input,
+    /// or a directory whose files are named inputs.
+    fn read_inputs(&self, inputs: &Path) -> Result<BTreeMap<String, String>, InputError> {
+        // paths that cannot be checked are read, to report why
+        let Some(path) = (self.input_paths(inputs).into_iter())
+            .find(|path| !matches!(path.try_exists(), Ok(false)))
+        else {
+            return Err(InputError::Missing);
+        };
+        if !path.is_dir() {
+            return Ok(BTreeMap::from([(
+                DEFAULT_INPUT.to_owned(),
+                read_input(&path)?,
+            )]));
+        }
+        let unreadable = |err| InputError::Unreadable(path.clone(), err);
+        let mut inputs = BTreeMap::new();
+        for entry in fs::read_dir(&path).map_err(unreadable)? {
+            let entry = entry.map_err(unreadable)?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // skipping hidden files, like the ones left by editors
+            if name.starts_with('.') || !entry.file_type().map_err(unreadable)?.is_file() {
+                continue;
+            }
+            inputs.insert(name, read_input(&entry.path())?);
+        }
+        if inputs.is_empty() {
+            return Err(InputError::Missing);
+        }
+        Ok(inputs)
     }
 
     fn is_empty(&self) -> bool {
         self.parts.iter().all(|p| p.is_none())
     }
+
+    /// The steps of the day that are measured
+    fn phases(&self) -> impl Iterator<Item = (Phase, &Part)> {
+        let parse = self.parse.as_ref().map(|parse| (Phase::Parse, parse));
+        let parts = (self.parts.iter().zip(1..))
+            .filter_map(|(part, p)| Some((Phase::Part(p), part.as_ref()?)));
+        parse.into_iter().chain(parts)
+    }
+}
+
+/// The parts of a day with a parsing step
+pub struct Parsed<'d, P> {
+    day: &'d mut Day,
+    parsed: Arc<ParsedInputs<P>>,
+}
+impl<P: Send + Sync + 'static> Parsed<'_, P> {
+    /// Add the first part, taking the parsed input or anything it can be borrowed as
+    pub fn add_part_1<B, T>(
+        &mut self,
+        fun: fn(&B) -> T,
+        multiline: bool,
+        long_running: bool,
+    ) -> &mut Self
+    where
+        P: Borrow<B>,
+        B: ?Sized + 'static,
+        T: Outcome + 'static,
+    {
+        let fun = PartFn::parsed_with(self.parsed.clone(), move |_: &Params| Ok(fun));
+        self.day.add_part_1(fun, multiline, long_running);
+        self
+    }
+    /// Add the second part, taking the parsed input or anything it can be borrowed as
+    pub fn add_part_2<B, T>(
+        &mut self,
+        fun: fn(&B) -> T,
+        multiline: bool,
+        long_running: bool,
+    ) -> &mut Self
+    where
+        P: Borrow<B>,
+        B: ?Sized + 'static,
+        T: Outcome + 'static,
+    {
+        let fun = PartFn::parsed_with(self.parsed.clone(), move |_: &Params| Ok(fun));
+        self.day.add_part_2(fun, multiline, long_running);
+        self
+    }
+    /// Add the first part, taking the parsed input and parameters
+    ///
+    /// `prepare` reads the parameters, and returns the function of the part taking them.
+    pub fn add_part_1_with_params<B, T, F, G>(
+        &mut self,
+        prepare: F,
+        multiline: bool,
+        long_running: bool,
+    ) -> &mut Self
+    where
+        P: Borrow<B>,
+        B: ?Sized + 'static,
+        T: Outcome + 'static,
+        F: Fn(&Params) -> Result<G, ParamError> + Copy + Send + Sync + RefUnwindSafe + 'static,
+        G: Fn(&B) -> T,
+    {
+        self.day.add_part_1(
+            PartFn::parsed_with(self.parsed.clone(), prepare),
+            multiline,
+            long_running,
+        );
+        self
+    }
+    /// Add the second part, taking the parsed input and parameters
+    ///
+    /// `prepare` reads the parameters, and returns the function of the part taking them.
+    pub fn add_part_2_with_params<B, T, F, G>(
+        &mut self,
+        prepare: F,
+        multiline: bool,
+        long_running: bool,
+    ) -> &mut Self
+    where
+        P: Borrow<B>,
+        B: ?Sized + 'static,
+        T: Outcome + 'static,
+        F: Fn(&Params) -> Result<G, ParamError> + Copy + Send + Sync + RefUnwindSafe + 'static,
+        G: Fn(&B) -> T,
+    {
+        self.day.add_part_2(
+            PartFn::parsed_with(self.parsed.clone(), prepare),
+            multiline,
+            long_running,
+        );
+        self
+    }
+}
+
+/// A step of a day that is measured on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Phase {
+    /// The parsing step shared by the parts
+    Parse,
+    /// A part, numbered from 1
+    Part(u8),
+}
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(p) => write!(f, "part {p}"),
+        }
+    }
+}
+
+/// An example input, with the expected answers
+#[derive(Debug)]
+pub struct Example {
+    name: String,
+    input: Cow<'static, str>,
+    answers: [Option<String>; 2],
+    /// Functions to run instead of the solutions
+    funs: [Option<PartFn>; 2],
+    /// Parameters replacing the ones of the parts
+    params: Params,
+}
+impl Example {
+    /// Set the expected answer of a part
+    pub fn expect(&mut self, part: u8, answer: impl Display) -> &mut Self {
+        assert!(part == 1 || part == 2, "Part {part} is invalid");
+        self.answers[part as usize - 1] = Some(answer.to_string());
+        self
+    }
+
+    /// Run a different function for a part on this example
+    pub fn override_part(&mut self, part: u8, fun: impl Into<PartFn>) -> &mut Self {
+        assert!(part == 1 || part == 2, "Part {part} is invalid");
+        self.funs[part as usize - 1] = Some(fun.into());
+        self
+    }
+
+    /// Set a parameter on this example, for the parts that have it
+    pub fn set_param(&mut self, name: &str, value: impl Display) -> &mut Self {
+        self.params.set(name, value.to_string());
+        self
+    }
+
+    /// The part to run on this example, if it has to be run
+    fn part(&self, day: &Day, part: usize) -> Option<Part> {
+        self.answers[part].as_ref()?;
+        let mut solution = day.parts[part].clone()?;
+        if let Some(fun) = self.funs[part].clone() {
+            solution.fun = fun
+        }
+        solution.params.override_with(&self.params);
+        Some(solution)
+    }
 }
 
 #[derive(Debug)]
@@ -350,7 +475,7 @@ impl Year {
     where
         F: FnOnce(&mut Day),
     {
-        if day < 1 || day > 25 {
+        if !(1..=25).contains(&day) {
             panic!("Day {day} is invalid, not an advent day")
         }
         if self.filters.accept_day(self.year, day) {
@@ -358,8 +483,12 @@ impl Year {
                 std::collections::btree_map::Entry::Vacant(v) => {
                     let mut entries = Day {
                         day: (self.year, day),
+                        parse: None,
+                        parsed: vec![],
                         parts: [None, None],
+                        examples: vec![],
                         filters: self.filters.clone(),
+                        source_dir: None,
                     };
                     build(&mut entries);
                     // do not create empty days
@@ -373,18 +502,6 @@ impl Year {
         self
     }
 
-    fn measure(
-        &self,
-        repeats: Option<NonZeroU32>,
-        inputs: &Path,
-    ) -> BTreeMap<u8, io::Result<[Option<Result<Measurements, FailedMeasurements>>; 2]>> {
-        log::info!("Measuring year {}", self.year);
-        self.solutions
-            .iter()
-            .map(|(day, sols)| (*day, sols.measure(repeats, inputs)))
-            .collect()
-    }
-
     fn is_empty(&self) -> bool {
         self.solutions.is_empty()
     }
@@ -424,87 +541,597 @@ impl Library {
         self
     }
 
+    /// Run all the examples, checking only their answers
+    fn run_examples(&self, jobs: NonZeroUsize, isolation: Isolation<'_>) -> ExampleMeasures {
+        let tasks: Vec<_> = self
+            .solutions
+            .values()
+            .flat_map(|y| y.solutions.values())
+            .flat_map(|d| {
+                d.examples.iter().flat_map(move |e| {
+                    (0..2).filter_map(move |p| Some((d.day, e, p, e.part(d, p)?)))
+                })
+            })
+            .collect();
+
+        let timing_lock = Mutex::new(());
+        let results = pool::map(jobs, &tasks, |((y, d), example, p, part)| {
+            log::info!("Checking {y}::{d}::{} on example {}", p + 1, example.name);
+            match isolation.for_part(part) {
+                Some(timeout) => isolation::measure(
+                    (*y, *d, Phase::Part(*p as u8 + 1)),
+                    Some(&example.name),
+                    None,
+                    &example.input,
+                    timeout,
+                    isolation.plugins,
+                    &timing_lock,
+                ),
+                None => part.measure(None, &example.input, &timing_lock),
+            }
+        });
+
+        self.clear_parsed();
+
+        let mut measures = ExampleMeasures::new();
+        for (((y, d), example, p, part), result) in tasks.iter().zip(results) {
+            measures.entry((*y, *d, example.name.clone())).or_default()[*p] =
+                Some(ExampleMeasure {
+                    expected: example.answers[*p].clone().unwrap(),
+                    params: part.params.clone(),
+                    result,
+                });
+        }
+        measures
+    }
+
+    /// Check that the parts can read their parameters, including on the examples
+    fn check_params(&self) -> anyhow::Result<()> {
+        for day in self.solutions.values().flat_map(|y| y.solutions.values()) {
+            let (y, d) = day.day;
+            for (part, p) in day.parts.iter().zip(1..) {
+                let Some(part) = part else { continue };
+                (part.fun.check_params(&part.params))
+                    .with_context(|| format!("Invalid parameters of {y}::{d}::{p}"))?;
+            }
+            for example in &day.examples {
+                for p in 0..2 {
+                    let Some(part) = example.part(day, p) else {
+                        continue;
+                    };
+                    part.fun.check_params(&part.params).with_context(|| {
+                        format!(
+                            "Invalid parameters of {y}::{d}::{} on example {}",
+                            p + 1,
+                            example.name
+                        )
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the inputs parsed for the parts, that the next runs read again
+    fn clear_parsed(&self) {
+        let days = self.solutions.values().flat_map(|y| y.solutions.values());
+        for parsed in days.flat_map(|d| &d.parsed) {
+            parsed.clear();
+        }
+    }
+
+    /// Read the inputs of all the days, warning about the days without any
+    ///
+    /// With `skip_missing` the days without inputs are left out.
+    fn read_inputs(&self, inputs: &Path, skip_missing: bool) -> Inputs {
+        log::info!("Reading inputs");
+        let mut read: Inputs = (self.solutions.values())
+            .flat_map(|y| y.solutions.values())
+            .map(|d| (d.day, d.read_inputs(inputs)))
+            .collect();
+        let days = |pred: fn(&InputError) -> bool| {
+            (read.iter())
+                .filter(|(_, inputs)| inputs.as_ref().is_err_and(pred))
+                .map(|((y, d), _)| format!("{y}::{d}"))
+                .join(", ")
+        };
+        let missing = days(|err| matches!(err, InputError::Missing));
+        if !missing.is_empty() {
+            log::warn!("No input in {} for {missing}", inputs.display());
+        }
+        let empty = days(|err| matches!(err, InputError::Empty(_)));
+        if !empty.is_empty() {
+            log::warn!("Empty input for {empty}");
+        }
+        if skip_missing {
+            read.retain(|_, inputs| !inputs.as_ref().is_err_and(InputError::is_skipped));
+        }
+        read
+    }
+
+    /// Measure all the solutions, running up to `jobs` parts at the same time
+    ///
+    /// Parts are scheduled independently, so the two parts of a day can run in parallel.
+    /// The timing passes are serialized through a lock: while a solution is being timed no other
+    /// timing is running, even if other workers are still computing answers.
     fn measure(
         &self,
-        repeats: Option<NonZeroU32>,
+        bench: Option<BenchSettings>,
         inputs: &Path,
-    ) -> BTreeMap<
-        u16,
-        BTreeMap<u8, io::Result<[Option<Result<Measurements, FailedMeasurements>>; 2]>>,
-    > {
-        self.solutions
+        skip_missing: bool,
+        jobs: NonZeroUsize,
+        isolation: Isolation<'_>,
+    ) -> Measures {
+        let inputs = self.read_inputs(inputs, skip_missing);
+
+        // Long running parts are scheduled first, so they do not end up trailing the others
+        let tasks: Vec<_> = inputs
             .iter()
-            .map(|(year, sols)| (*year, sols.measure(repeats, inputs)))
-            .collect()
+            .filter_map(|(&(y, d), inputs)| Some(((y, d), inputs.as_ref().ok()?)))
+            .flat_map(|((y, d), inputs)| {
+                inputs.iter().flat_map(move |(name, input)| {
+                    self.solutions[&y].solutions[&d]
+                        .phases()
+                        .map(move |(phase, part)| {
+                            ((y, d, name.as_str(), phase), part, input.as_str())
+                        })
+                })
+            })
+            .sorted_by_key(|(_, part, _)| !part.long_running)
+            .collect();
+
+        let timing_lock = Mutex::new(());
+        let mut results: BTreeMap<_, _> =
+            pool::map(jobs, &tasks, |((y, d, name, phase), part, input)| {
+                measure_phase(
+                    (*y, *d, name, *phase),
+                    part,
+                    input,
+                    bench,
+                    isolation,
+                    &timing_lock,
+                )
+            })
+            .into_iter()
+            .zip(tasks.iter().map(|(key, _, _)| *key))
+            .map(|(res, key)| (key, res))
+            .collect();
+        self.clear_parsed();
+
+        let mut measures: BTreeMap<u16, BTreeMap<_, _>> = BTreeMap::new();
+        for ((y, d), inputs) in &inputs {
+            let day = match inputs {
+                Ok(inputs) => Ok(inputs
+                    .keys()
+                    .map(|name| {
+                        let mut result = |phase| results.remove(&(*y, *d, name.as_str(), phase));
+                        let measures = InputMeasures {
+                            parse: result(Phase::Parse).map(|r| r.map(|m| m.time)),
+                            parts: [1, 2].map(|p| result(Phase::Part(p))),
+                        };
+                        (name.clone(), measures)
+                    })
+                    .collect()),
+                Err(err) => Err(err.clone()),
+            };
+            measures.entry(*y).or_default().insert(*d, day);
+        }
+        measures
+    }
+}
+
+/// Measure a phase of a day on an input, in a child process if the part must be isolated
+fn measure_phase(
+    (y, d, name, phase): (u16, u8, &str, Phase),
+    part: &Part,
+    input: &str,
+    bench: Option<BenchSettings>,
+    isolation: Isolation<'_>,
+    timing_lock: &Mutex<()>,
+) -> Result<Measurements, FailedMeasurements> {
+    let step = match phase {
+        Phase::Parse => format!("{y}::{d} parsing"),
+        Phase::Part(p) => format!("{y}::{d}::{p}"),
+    };
+    if name == DEFAULT_INPUT {
+        log::info!("Measuring {step}");
+    } else {
+        log::info!("Measuring {step} on input {name}");
+    }
+    match isolation.for_part(part) {
+        Some(timeout) => isolation::measure(
+            (y, d, phase),
+            None,
+            bench,
+            input,
+            timeout,
+            isolation.plugins,
+            timing_lock,
+        ),
+        None => part.measure(bench, input, timing_lock),
     }
 }
 
-#[derive(Parser)]
+#[derive(Debug, Parser)]
 struct Args {
     #[clap(default_value_t)]
-    /// What problems to run
+    /// What problems to run, e.g. `2022::1..10 | 2023 - long_running`, `wrong | slow>100ms` or
+    /// `@PRESET` for a preset of aoc-runner.toml
     problems: filters::Filters,
-    /// Number of repeats used to measure running time (0 to not measure times)
+    /// Minimum number of samples used to measure running time (0 to not measure times)
     #[clap(long, short, default_value = "1")]
     repeats: u32,
+    /// Untimed runs before sampling, in addition to the one computing the answer
+    #[clap(long, default_value = "0")]
+    warmup: u32,
+    /// Keep sampling each part until this much time is spent on it
+    #[clap(long, value_parser = humantime::parse_duration)]
+    time_budget: Option<Duration>,
+    /// Keep sampling each part until the 95% confidence interval of the mean is narrower than
+    /// this fraction of the mean
+    #[clap(long)]
+    target_ci: Option<f64>,
+    /// Maximum number of samples for each part
+    #[clap(long, default_value = "10000")]
+    max_samples: NonZeroU32,
     /// Directory for the inputs
-    #[clap(long, short, default_value = "./inputs")]
+    ///
+    /// The input of a day is `YEAR/DAY`, either a file or a directory of named inputs. The day can
+    /// be zero-padded and followed by `.txt`, and an `input.txt` in the crate of the solution is
+    /// used if there is none.
+    #[clap(long, short, default_value = "./inputs", global = true)]
     inputs: PathBuf,
+    /// Leave the days without an input out of the report and its totals
+    ///
+    /// They are shown as skipped otherwise, and the totals are not known to be correct.
+    #[clap(long)]
+    skip_missing: bool,
     /// File with the correct answers [default: INPUTS/answers.json]
-    #[clap(long, short)]
+    #[clap(long, short, global = true)]
     answers: Option<Option<PathBuf>>,
-    /// File with the baseline for the benches
-    #[clap(long, short)]
-    baseline: Option<Option<PathBuf>>,
-    /// File where to save the new baseline
+    /// Baselines to compare against, by name or as a file [default: ./baseline.json if present]
+    ///
+    /// Can be given multiple times. Values ending in `.json` or containing a path separator are
+    /// files, the others are names of baselines in the baselines directory.
+    #[clap(long, short, num_args = 0..=1, default_missing_value = DEFAULT_BASELINE)]
+    baseline: Vec<String>,
+    /// Directory of the named baselines
+    #[clap(long, default_value = "./baselines", global = true)]
+    baselines_dir: PathBuf,
+    /// Name or file where to save the new baseline [default: the first baseline]
+    #[clap(long)]
+    save_baseline: Option<Option<String>>,
+    /// History of the runs, used for the trends [default: ./history.jsonl]
+    #[clap(long)]
+    history: Option<Option<PathBuf>>,
+    /// Append this run to the history [default: the history file]
+    #[clap(long)]
+    record: Option<Option<PathBuf>>,
+    /// Also compare against the history: `last[:N]`, `commit:REV` or `best`
+    #[clap(long)]
+    compare_history: Option<history::Selector>,
+    /// Number of parts to run in parallel (0 to use all the available cores)
+    ///
+    /// Timing passes are never run concurrently with each other, but they can overlap with other
+    /// solutions computing their answers. Use 1 for the most stable timings.
+    #[clap(long, short, default_value = "1")]
+    jobs: usize,
+    /// Run each part in a child process, so crashes do not take down the runner
+    ///
+    /// Isolated parts only hold the timing lock while they are timed, and the time spent waiting
+    /// for it does not count for their timeout.
     #[clap(long)]
-    save_baseline: Option<Option<PathBuf>>,
+    isolate: bool,
+    /// Only run the examples, without reading the inputs
+    #[clap(long)]
+    examples_only: bool,
+    /// Maximum running time of each part, including the timing repetitions (implies --isolate)
+    ///
+    /// Parts can override it with the `timeout` key in their metadata.
+    #[clap(long, value_parser = humantime::parse_duration)]
+    timeout: Option<Duration>,
+    /// Plugin, or directory of plugins, whose solutions replace the ones of the library
+    ///
+    /// Plugins are solutions built as dynamic libraries, e.g. the one built by
+    /// `cargo rustc -p aoc_2024_14 --crate-type cdylib` in `target/debug`. Can be given multiple
+    /// times, the later plugins replacing the former.
+    #[clap(long)]
+    plugins: Vec<PathBuf>,
+    /// Keep running, rerunning the selected days when their solutions, inputs or answers change
+    ///
+    /// Must be run at the root of the workspace. The changed solutions are rebuilt as plugins. The
+    /// report is redrawn, or written to HTML_FILE as a page reloading itself.
+    #[clap(long, value_name = "HTML_FILE")]
+    watch: Option<Option<PathBuf>>,
+    /// Browse the results in an interactive terminal UI, running the parts in the background
+    ///
+    /// Parts can be run again, compared with each baseline in turn, and have their answer
+    /// accepted into the answers file.
+    #[clap(long, conflicts_with = "watch")]
+    tui: bool,
+    /// Format of the report [default: term if stdout is a terminal, html otherwise]
+    #[clap(long, short, value_enum, global = true)]
+    format: Option<Format>,
+    /// Exit with an error if, compared to any baseline, a part became significantly slower than
+    /// --max-slowdown or a correct answer became wrong or failed
+    #[clap(long, global = true)]
+    fail_on_regression: bool,
+    /// Slowdown, in percent, that counts as a regression
+    #[clap(long, default_value = "10", global = true)]
+    max_slowdown: f64,
+    /// Run as the child process of an isolated part
+    #[clap(long, hide = true)]
+    isolated_worker: bool,
+    /// Example the isolated part runs on
+    #[clap(long, hide = true, requires = "isolated_worker")]
+    isolated_example: Option<String>,
+    /// Measure the parsing step of the day instead of a part
+    #[clap(long, hide = true, requires = "isolated_worker")]
+    isolated_parse: bool,
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Report the differences between two stored baselines, without running anything
+    Compare {
+        /// Baseline to compare against, by name or as a file
+        old: String,
+        /// Baseline to compare, by name or as a file
+        new: String,
+        /// What problems to compare
+        #[clap(default_value_t)]
+        problems: filters::Filters,
+    },
+    /// Create the crate of a new solution from a template, with an empty input
+    ///
+    /// The template is read from `templates/<year>` or `templates/default` in the workspace, if
+    /// present. The solution is added to the library, and runs once it is rebuilt.
+    NewDay {
+        #[clap(value_parser = clap::value_parser!(u16).range(2015..))]
+        year: u16,
+        #[clap(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Crate of `commons` the solution depends on, can be given multiple times
+        #[clap(long = "with", value_name = "CRATE")]
+        commons: Vec<String>,
+        /// Root of the workspace
+        #[clap(long, default_value = ".")]
+        workspace: PathBuf,
+    },
+    /// Record the answer of a part as correct in the answers file
+    ///
+    /// Unless the answer is given, the part is run and its answers on all its inputs are recorded.
+    Accept {
+        /// The part, as `YEAR::DAY::PART`
+        part: answers::PartSpec,
+        /// The answer to record, instead of running the part
+        answer: Option<String>,
+        /// Only record the answer on this input [default: all the inputs when running the part,
+        /// the default input for a given answer]
+        #[clap(long, value_name = "NAME")]
+        input_name: Option<String>,
+    },
+    /// Record an answer of a part as rejected by the site in the answers file
+    ///
+    /// Unless the answer is given, the part is run and its answers on all its inputs are recorded.
+    /// The report then shows any answer known to be wrong, including the ones beyond a rejected
+    /// answer that was too high or too low.
+    Reject {
+        /// The part, as `YEAR::DAY::PART`
+        part: answers::PartSpec,
+        /// The answer to record, instead of running the part
+        answer: Option<String>,
+        /// The site said the answer is too high
+        #[clap(long, conflicts_with = "too_low")]
+        too_high: bool,
+        /// The site said the answer is too low
+        #[clap(long)]
+        too_low: bool,
+        /// Only record the answer on this input [default: all the inputs when running the part,
+        /// the default input for a given answer]
+        #[clap(long, value_name = "NAME")]
+        input_name: Option<String>,
+    },
+    /// Check the answers file against the solutions of the library, and print it formatted
+    Answers {
+        /// Rewrite the file formatted instead of printing it
+        #[clap(long)]
+        write: bool,
+    },
 }
 
+/// Baseline used if none is given
+const DEFAULT_BASELINE: &str = "./baseline.json";
+
+mod answer;
+mod answers;
+mod bench;
+mod config;
 mod filters;
+mod history;
+mod isolation;
+mod memory;
+mod panics;
+mod params;
+pub mod plugin;
+mod pool;
+mod scaffold;
+pub mod schema;
+mod tui;
+mod watch;
 
 fn main<F>(
     build: F,
     Args {
         problems,
         repeats,
+        warmup,
+        time_budget,
+        target_ci,
+        max_samples,
         inputs,
+        skip_missing,
         answers,
         baseline,
+        baselines_dir,
         save_baseline,
+        history,
+        record,
+        compare_history,
+        jobs,
+        isolate,
+        examples_only,
+        timeout,
+        plugins,
+        watch,
+        tui,
+        format,
+        fail_on_regression,
+        max_slowdown,
+        isolated_worker,
+        isolated_example,
+        isolated_parse,
+        command,
     }: Args,
 ) -> anyhow::Result<()>
 where
     F: FnOnce(&mut Library),
 {
-    let repeats = NonZeroU32::new(repeats);
+    let bench = NonZeroU32::new(repeats).map(|min_samples| BenchSettings {
+        warmup,
+        min_samples,
+        max_samples: max_samples.max(min_samples),
+        budget: time_budget,
+        target_ci,
+    });
+    let jobs = NonZeroUsize::new(jobs).unwrap_or_else(|| {
+        thread::available_parallelism().unwrap_or_else(|err| {
+            log::warn!("Cannot detect available parallelism, running serially: {err}");
+            NonZeroUsize::MIN
+        })
+    });
+    if isolated_worker {
+        let mut lib = Library {
+            solutions: BTreeMap::new(),
+            filters: Rc::new(problems),
+        };
+        (build)(&mut lib);
+        plugin::load(&plugins, &mut lib).context("Cannot load the plugins")?;
+        lib.check_params()?;
+        return isolation::worker(&lib, bench, isolated_example.as_deref(), isolated_parse);
+    }
+    if let Some(Command::NewDay {
+        year,
+        day,
+        commons,
+        workspace,
+    }) = &command
+    {
+        return scaffold::new_day(workspace, &inputs, *year, *day, commons);
+    }
     log::info!("Parsing databases");
+    let answers_file = answers
+        .clone()
+        .flatten()
+        .unwrap_or_else(|| inputs.join("answers.json"));
     let answers = match answers {
-        Some(Some(answers)) => Some(read_answers(&answers).context("Cannot read answer file")?),
+        // the commands on the answers read the file themselves, that may not exist yet
+        _ if matches!(
+            command,
+            Some(Command::Accept { .. } | Command::Reject { .. } | Command::Answers { .. })
+        ) =>
+        {
+            None
+        }
+        Some(Some(answers)) => Some(answers::read(&answers).context("Cannot read answer file")?),
         Some(None) => Some(
-            read_answers(inputs.join("answers.json")).context("Cannot read default answer file")?,
+            answers::read(inputs.join("answers.json"))
+                .context("Cannot read default answer file")?,
         ),
         // try to read the default file, but silently ignore errors
-        None => read_answers(inputs.join("answers.json")).ok(),
+        None => answers::read(inputs.join("answers.json")).ok(),
     }
     // Empty answers
     .unwrap_or_default();
+
+    if let Some(Command::Compare {
+        old,
+        new,
+        mut problems,
+    }) = command
+    {
+        let old_baseline = read_baseline(baseline_path(&baselines_dir, &old))
+            .with_context(|| format!("Cannot read baseline {old}"))?;
+        let new_baseline = read_baseline(baseline_path(&baselines_dir, &new))
+            .with_context(|| format!("Cannot read baseline {new}"))?;
+        problems.set_status(Some(&new_baseline), &answers);
+        log::info!("Building report");
+        let report = Report::new(
+            Rc::new(problems.clone()),
+            measures_from_baseline(new_baseline, &problems),
+            ExampleMeasures::new(),
+            answers,
+            vec![(baseline_name(&old), old_baseline)],
+            &Trends::new(),
+        );
+        print_report(&report, format)?;
+        return check_regressions(&report, fail_on_regression, max_slowdown);
+    }
+
     let save_baseline = save_baseline.map(|sb| {
-        sb.or(baseline.clone().flatten())
-            .unwrap_or_else(|| PathBuf::from("./baseline.json"))
+        baseline_path(
+            &baselines_dir,
+            sb.as_deref()
+                .or(baseline.first().map(String::as_str))
+                .unwrap_or(DEFAULT_BASELINE),
+        )
     });
-    let baselines = match baseline {
-        Some(Some(baseline)) => {
-            Some(read_baseline(&baseline).context("Cannot read baseline file")?)
-        }
-        Some(None) => {
-            Some(read_baseline(&"./baseline.json").context("Cannot read default baseline file")?)
-        }
+    let history_file = history
+        .clone()
+        .flatten()
+        .unwrap_or_else(|| PathBuf::from("./history.jsonl"));
+    let record = record.map(|r| r.unwrap_or_else(|| history_file.clone()));
+    let history = match history {
+        Some(_) => Some(History::read(&history_file).context("Cannot read history file")?),
         // try to read the default file, but silently ignore errors
-        None => read_baseline(&"./baseline.json").ok(),
+        None => History::read(&history_file).ok(),
     }
-    // Empty baseline if nothing is given
     .unwrap_or_default();
+    let mut baselines: Vec<(String, Baselines)> = if baseline.is_empty() {
+        // try to read the default file, but silently ignore errors
+        read_baseline(DEFAULT_BASELINE)
+            .ok()
+            .map(|b| (baseline_name(DEFAULT_BASELINE), b))
+            .into_iter()
+            .collect()
+    } else {
+        baseline
+            .iter()
+            .map(|name| {
+                read_baseline(baseline_path(&baselines_dir, name))
+                    .with_context(|| format!("Cannot read baseline {name}"))
+                    .map(|b| (baseline_name(name), b))
+            })
+            .try_collect()?
+    };
+    if let Some(selector) = compare_history {
+        baselines.push((
+            format!("history {selector}"),
+            history
+                .baseline(&selector)
+                .context("Cannot select the runs to compare against")?,
+        ));
+    }
+    // editing the answers only runs the part, while checking them needs all the solutions
+    let mut problems = match &command {
+        Some(Command::Accept { part, .. } | Command::Reject { part, .. }) => part.filters(),
+        Some(Command::Answers { .. }) => Filters::default(),
+        _ => problems,
+    };
+    // the predicates on the status of the parts use the first baseline
+    problems.set_status(baselines.first().map(|(_, b)| b), &answers);
     log::info!("Building library");
     let library = {
         let mut lib = Library {
@@ -512,29 +1139,251 @@ where
             filters: Rc::new(problems),
         };
         (build)(&mut lib);
+        if !plugins.is_empty() {
+            log::info!("Loading plugins");
+            plugin::load(&plugins, &mut lib).context("Cannot load the plugins")?;
+        }
+        lib.check_params()?;
         lib
     };
-    log::info!("Executing tests");
-    let measures = library.measure(repeats, &inputs);
+    let isolation = Isolation {
+        forced: isolate,
+        timeout,
+        plugins: &plugins,
+    };
+    match command {
+        Some(Command::Accept {
+            part,
+            answer,
+            input_name,
+        }) => {
+            return answers::edit(
+                &answers_file,
+                &library,
+                part,
+                answer,
+                input_name,
+                answers::Edit::Accept,
+                || library.measure(None, &inputs, skip_missing, jobs, isolation),
+            )
+        }
+        Some(Command::Reject {
+            part,
+            answer,
+            too_high,
+            too_low,
+            input_name,
+        }) => {
+            let hint = if too_high {
+                Some(answers::Hint::TooHigh)
+            } else if too_low {
+                Some(answers::Hint::TooLow)
+            } else {
+                None
+            };
+            return answers::edit(
+                &answers_file,
+                &library,
+                part,
+                answer,
+                input_name,
+                answers::Edit::Reject(hint),
+                || library.measure(None, &inputs, skip_missing, jobs, isolation),
+            );
+        }
+        Some(Command::Answers { write }) => {
+            return answers::check(&answers_file, &library, &inputs, write)
+        }
+        _ => (),
+    }
+    if tui {
+        return tui::Tui {
+            library: &library,
+            inputs: &inputs,
+            skip_missing,
+            bench,
+            jobs,
+            isolation,
+            answers_file: &answers_file,
+            trends: &history.trends(),
+        }
+        .run(answers, baselines);
+    }
+    if let Some(page) = watch {
+        let trends = history.trends();
+        return watch::watch(
+            library,
+            &inputs,
+            &answers_file,
+            &plugins,
+            |library, plugins| {
+                let isolation = Isolation {
+                    plugins,
+                    ..isolation
+                };
+                // the answers are read again, as they are watched
+                let answers = match answers::read(&answers_file) {
+                    Ok(answers) => answers,
+                    Err(err) => {
+                        if answers_file.exists() {
+                            log::warn!("Ignoring the answers: {err:?}")
+                        }
+                        Answers::new()
+                    }
+                };
+                log::info!("Running examples");
+                let examples = library.run_examples(jobs, isolation);
+                let measures = if examples_only {
+                    Measures::new()
+                } else {
+                    log::info!("Executing tests");
+                    library.measure(bench, &inputs, skip_missing, jobs, isolation)
+                };
+                log::info!("Building report");
+                let report = Report::new(
+                    library.filters.clone(),
+                    measures,
+                    examples,
+                    answers,
+                    baselines.clone(),
+                    &trends,
+                );
+                match &page {
+                    Some(page) => {
+                        let html = html_page(&report, true)
+                            .expect("Writing in a html buffer should be error_free");
+                        fs::write(page, html.to_string())
+                            .with_context(|| format!("Cannot write {}", page.display()))
+                    }
+                    None => {
+                        if io::stdout().is_terminal() {
+                            // clearing the screen, so the report is always at the top
+                            print!("\x1b[2J\x1b[H");
+                        }
+                        print_report(&report, format)
+                    }
+                }
+            },
+        );
+    }
+    log::info!("Running examples");
+    let examples = library.run_examples(jobs, isolation);
+    let measures = if examples_only {
+        Measures::new()
+    } else {
+        log::info!("Executing tests");
+        library.measure(bench, &inputs, skip_missing, jobs, isolation)
+    };
     // saving baselines
-    if let Some(save_baseline) = save_baseline {
+    if let Some(save_baseline) = save_baseline.filter(|_| !examples_only) {
         log::info!("Saving baselines");
-        if let Err(err) = dump_baseline(&save_baseline, &measures, &baselines) {
+        // parts that were not run keep their previous baseline
+        let old_baseline = read_baseline(&save_baseline).unwrap_or_default();
+        if let Err(err) = dump_baseline(&save_baseline, &measures, &old_baseline) {
             log::warn!("Failed to save baselines: {err:?}")
         }
     }
 
-    log::info!("Building report");
-    let report = Report::new(library.filters, measures, answers, baselines);
+    if let Some(record) = record.filter(|_| !examples_only) {
+        log::info!("Recording run in history");
+        let no_baselines = Baselines::new();
+        let run = history::Run::now(
+            library.filters.to_string(),
+            merged_baselines(&measures, &no_baselines),
+        );
+        if let Err(err) = history::append(&record, &run) {
+            log::warn!("Failed to record run: {err:?}")
+        }
+    }
 
-    print!(
-        "{}",
-        html_page(report).expect("Writing in a html buffer should be error_free")
+    log::info!("Building report");
+    let report = Report::new(
+        library.filters,
+        measures,
+        examples,
+        answers,
+        baselines,
+        &history.trends(),
     );
+    print_report(&report, format)?;
+    check_regressions(&report, fail_on_regression, max_slowdown)
+}
+
+fn print_report(report: &Report, format: Option<Format>) -> anyhow::Result<()> {
+    let format = format.unwrap_or_else(|| {
+        if io::stdout().is_terminal() {
+            Format::Term
+        } else {
+            Format::Html
+        }
+    });
+    match format {
+        Format::Html => print!(
+            "{}",
+            html_page(report, false).expect("Writing in a html buffer should be error_free")
+        ),
+        Format::Json => {
+            log::info!("Serializing report");
+            let mut stdout = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut stdout, &report.to_schema())
+                .context("Cannot write the report")?;
+            writeln!(stdout).context("Cannot write the report")?;
+        }
+        Format::Term => {
+            log::info!("Rendering report");
+            let color = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+            let mut buf = String::new();
+            report
+                .render_term(&mut buf, color)
+                .expect("Writing in a string should be error free");
+            print!("{buf}");
+        }
+        Format::Junit => {
+            log::info!("Rendering report");
+            let mut buf = String::new();
+            report
+                .render_junit(&mut buf)
+                .expect("Writing in a string should be error free");
+            print!("{buf}");
+        }
+    }
+    Ok(())
+}
+
+fn check_regressions(
+    report: &Report,
+    fail_on_regression: bool,
+    max_slowdown: f64,
+) -> anyhow::Result<()> {
+    if !fail_on_regression {
+        return Ok(());
+    }
+    let regressions = report.regressions(max_slowdown / 100.);
+    for regression in &regressions {
+        log::error!("Regression: {regression}")
+    }
+    if !regressions.is_empty() {
+        bail!("Found {} regressions", regressions.len())
+    }
     Ok(())
 }
 
-fn html_page(report: Report) -> Result<impl Display, fmt::Error> {
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// A standalone html page
+    Html,
+    /// Colored tables for the terminal
+    Term,
+    /// JUnit XML, with a testsuite for each year and a testcase for each part
+    Junit,
+    /// A JSON document, following the schema in `runner::schema`
+    Json,
+}
+
+/// The report as a standalone page
+///
+/// A `live` page reloads itself, to follow the report rewritten by the watch mode.
+fn html_page(report: &Report, live: bool) -> Result<impl Display, fmt::Error> {
     log::info!("Rendering report");
     let mut buf = Buffer::new();
     buf.doctype();
@@ -556,6 +1405,11 @@ fn html_page(report: Report) -> Result<impl Display, fmt::Error> {
         env!("CARGO_PKG_NAME"),
         env!("CARGO_PKG_VERSION")
     ));
+    if live {
+        head.meta()
+            .attr("http-equiv=\"refresh\"")
+            .attr(&format!("content=\"{}\"", LIVE_RELOAD.as_secs()));
+    }
     writeln!(head.style(), "{}", include_str!("report_style.css"))?;
     let mut body = buf.body();
     report.render(&mut body)?;
@@ -564,14 +1418,275 @@ fn html_page(report: Report) -> Result<impl Display, fmt::Error> {
 
 mod report;
 
+/// Interval between the reloads of a live page
+const LIVE_RELOAD: Duration = Duration::from_secs(2);
+
+/// Results of a day on an input
+#[derive(Debug, Default)]
+struct InputMeasures {
+    /// Running time of the parsing step, if the day has one
+    parse: Option<Result<Option<TimeStats>, FailedMeasurements>>,
+    parts: [Option<Result<Measurements, FailedMeasurements>>; 2],
+}
+
+/// Results of all the parts, on each input of the days
+type Measures = BTreeMap<u16, BTreeMap<u8, DayMeasures>>;
+
+/// Results of a day on each of its inputs, or why they could not be read
+type DayMeasures = Result<BTreeMap<String, InputMeasures>, InputError>;
+
+/// Inputs of the days, by name
+type Inputs = BTreeMap<(u16, u8), Result<BTreeMap<String, String>, InputError>>;
+
+/// Why the inputs of a day could not be read
+#[derive(Debug)]
+enum InputError {
+    /// None of the input paths exists, or the directory has no files
+    Missing,
+    /// The input file is empty or has only whitespace, like the one created by `new-day`
+    Empty(PathBuf),
+    /// The input file is not valid UTF-8
+    NotUtf8(PathBuf),
+    /// The input could not be read, e.g. for its permissions
+    Unreadable(PathBuf, io::Error),
+}
+impl InputError {
+    /// If the day is skipped instead of failing, having no input yet
+    fn is_skipped(&self) -> bool {
+        matches!(self, InputError::Missing | InputError::Empty(_))
+    }
+}
+impl Clone for InputError {
+    fn clone(&self) -> Self {
+        match self {
+            InputError::Missing => InputError::Missing,
+            InputError::Empty(path) => InputError::Empty(path.clone()),
+            InputError::NotUtf8(path) => InputError::NotUtf8(path.clone()),
+            InputError::Unreadable(path, err) => {
+                InputError::Unreadable(path.clone(), io::Error::new(err.kind(), err.to_string()))
+            }
+        }
+    }
+}
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing => write!(f, "No input"),
+            InputError::Empty(path) => write!(f, "{} is empty", path.display()),
+            InputError::NotUtf8(path) => write!(f, "{} is not valid UTF-8", path.display()),
+            InputError::Unreadable(path, err) => write!(f, "Cannot read {}: {err}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Read an input file, that must be non-empty UTF-8
+fn read_input(path: &Path) -> Result<String, InputError> {
+    let content = fs::read(path).map_err(|err| InputError::Unreadable(path.to_owned(), err))?;
+    let content = String::from_utf8(content).map_err(|_| InputError::NotUtf8(path.to_owned()))?;
+    if content.trim().is_empty() {
+        return Err(InputError::Empty(path.to_owned()));
+    }
+    Ok(content)
+}
+
+/// Result of a part on an example, with the expected answer
+#[derive(Debug)]
+struct ExampleMeasure {
+    expected: String,
+    /// Parameters of the part on the example
+    params: Params,
+    result: Result<Measurements, FailedMeasurements>,
+}
+
+/// Results of the parts on each example, by year, day and example name
+type ExampleMeasures = BTreeMap<(u16, u8, String), [Option<ExampleMeasure>; 2]>;
+
+/// Name of the input of a day with a single input file
+const DEFAULT_INPUT: &str = "default";
+
+/// Values for each input of a day
+///
+/// When the default input is the only one, the value is serialized alone, in the same format used
+/// before days could have multiple inputs.
+#[derive(Debug, Clone)]
+struct PerInput<T>(BTreeMap<String, T>);
+impl<T> PerInput<T> {
+    fn get(&self, input: &str) -> Option<&T> {
+        self.0.get(input)
+    }
+
+    /// The value, if the default input is the only one
+    fn single(&self) -> Option<&T> {
+        match self.0.iter().exactly_one() {
+            Ok((name, value)) if name == DEFAULT_INPUT => Some(value),
+            _ => None,
+        }
+    }
+}
+impl<T> Default for PerInput<T> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+impl<T: Serialize> Serialize for PerInput<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.single() {
+            Some(value) => value.serialize(serializer),
+            None => self.0.serialize(serializer),
+        }
+    }
+}
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for PerInput<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr<T> {
+            Single(T),
+            PerInput(BTreeMap<String, T>),
+        }
+        Ok(Self(match Repr::deserialize(deserializer)? {
+            Repr::Single(value) => BTreeMap::from([(DEFAULT_INPUT.to_owned(), value)]),
+            Repr::PerInput(values) => values,
+        }))
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 struct Baseline {
     #[serde(default)]
     answer: Option<String>,
+    /// Mean running time, the only timing information in older baselines
     #[serde(default)]
     time: Option<Duration>,
+    #[serde(default)]
+    timing: Option<TimeStats>,
+    #[serde(default)]
+    memory: Option<MemoryStats>,
+}
+impl Baseline {
+    fn time_stats(&self) -> Option<TimeStats> {
+        self.timing.or_else(|| self.time.map(TimeStats::single))
+    }
+}
+
+/// Baselines of a day on an input
+///
+/// Serialized as the array of the parts, followed by the parsing step if the day has one.
+#[derive(Debug, Clone, Default)]
+struct InputBaseline {
+    parts: [Option<Baseline>; 2],
+    parse: Option<Baseline>,
+}
+impl InputBaseline {
+    fn phase(&self, phase: Phase) -> Option<&Baseline> {
+        match phase {
+            Phase::Parse => self.parse.as_ref(),
+            Phase::Part(p) => self.parts[p as usize - 1].as_ref(),
+        }
+    }
+
+    fn phase_mut(&mut self, phase: Phase) -> &mut Option<Baseline> {
+        match phase {
+            Phase::Parse => &mut self.parse,
+            Phase::Part(p) => &mut self.parts[p as usize - 1],
+        }
+    }
+
+    fn phases(&self) -> impl DoubleEndedIterator<Item = (Phase, &Baseline)> {
+        [Phase::Parse, Phase::Part(1), Phase::Part(2)]
+            .into_iter()
+            .filter_map(|phase| Some((phase, self.phase(phase)?)))
+    }
+}
+impl<'de> Deserialize<'de> for InputBaseline {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut phases = Vec::<Option<Baseline>>::deserialize(deserializer)?.into_iter();
+        if !(2..=3).contains(&phases.len()) {
+            return Err(serde::de::Error::invalid_length(
+                phases.len(),
+                &"the two parts and the optional parsing step",
+            ));
+        }
+        Ok(Self {
+            parts: [phases.next().unwrap(), phases.next().unwrap()],
+            parse: phases.next().flatten(),
+        })
+    }
+}
+
+type Baselines = BTreeMap<u16, BTreeMap<u8, PerInput<InputBaseline>>>;
+
+/// Location of a baseline given by name or as a file
+fn baseline_path(baselines_dir: &Path, baseline: &str) -> PathBuf {
+    if baseline.ends_with(".json") || baseline.contains(['/', std::path::MAIN_SEPARATOR]) {
+        PathBuf::from(baseline)
+    } else {
+        baselines_dir.join(format!("{baseline}.json"))
+    }
+}
+
+/// Name of a baseline given by name or as a file
+fn baseline_name(baseline: &str) -> String {
+    Path::new(baseline)
+        .file_stem()
+        .map_or_else(|| baseline.to_owned(), |s| s.to_string_lossy().into_owned())
+}
+
+/// Measures equivalent to the ones that produced a baseline
+///
+/// Baselines do not record which answers are multiline, so any answer spanning multiple lines is
+/// considered so.
+fn measures_from_baseline(baselines: Baselines, filters: &Filters) -> Measures {
+    baselines
+        .into_iter()
+        .map(|(y, days)| {
+            (
+                y,
+                days.into_iter()
+                    .filter(|(d, _)| filters.accept_day(y, *d))
+                    .map(|(d, inputs)| {
+                        let inputs: BTreeMap<_, _> = inputs
+                            .0
+                            .into_iter()
+                            .map(|(name, InputBaseline { parts, parse })| {
+                                let mut p = 0;
+                                let parts = parts.map(|b| {
+                                    p += 1;
+                                    let b = b.filter(|_| filters.accept_part(y, d, p, None))?;
+                                    let time = b.time_stats();
+                                    let answer = b.answer?;
+                                    Some(Ok(Measurements {
+                                        multiline: answer.contains('\n'),
+                                        answer,
+                                        time,
+                                        memory: b.memory,
+                                    }))
+                                });
+                                let parse = parse.map(|b| Ok(b.time_stats()));
+                                (name, InputMeasures { parse, parts })
+                            })
+                            .filter(|(_, meas)| meas.parts.iter().any(Option::is_some))
+                            .collect();
+                        (d, Ok(inputs))
+                    })
+                    .filter(|(_, inputs)| inputs.as_ref().is_ok_and(|i| !i.is_empty()))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        })
+        .filter(|(_, days)| !days.is_empty())
+        .collect()
 }
-type Baselines = BTreeMap<u16, BTreeMap<u8, [Option<Baseline>; 2]>>;
 
 fn read_baseline(file: impl AsRef<Path>) -> anyhow::Result<Baselines> {
     let file = File::open(file).context("Cannot open baseline file")?;
@@ -580,66 +1695,143 @@ fn read_baseline(file: impl AsRef<Path>) -> anyhow::Result<Baselines> {
 
 fn dump_baseline(
     save_baseline: &PathBuf,
-    measures: &BTreeMap<
-        u16,
-        BTreeMap<u8, Result<[Option<Result<Measurements, FailedMeasurements>>; 2], io::Error>>,
-    >,
+    measures: &Measures,
     old_baselines: &Baselines,
 ) -> anyhow::Result<()> {
+    if let Some(dir) = save_baseline.parent() {
+        fs::create_dir_all(dir).context("Cannot create the baseline directory")?;
+    }
+    let file = File::create(save_baseline).context("Cannot open save file")?;
+    serde_json::to_writer(file, &merged_baselines(measures, old_baselines))
+        .context("Cannot serialize measurements")?;
+    Ok(())
+}
+
+/// Baselines from the new measurements, falling back to the old baselines for missing parts
+fn merged_baselines<'a>(
+    measures: &'a Measures,
+    old_baselines: &'a Baselines,
+) -> impl Serialize + 'a {
     #[derive(Serialize)]
     struct BorrowedBaseline<'a> {
         #[serde(skip_serializing_if = "Option::is_none")]
         answer: Option<&'a str>,
         #[serde(skip_serializing_if = "Option::is_none")]
         time: Option<&'a Duration>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timing: Option<&'a TimeStats>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        memory: Option<&'a MemoryStats>,
     }
     impl<'a> BorrowedBaseline<'a> {
-        fn from_measurement(Measurements { answer, time, .. }: &'a Measurements) -> Self {
+        fn from_measurement(
+            Measurements {
+                answer,
+                time,
+                memory,
+                ..
+            }: &'a Measurements,
+        ) -> Self {
             Self {
-                answer: Some(&answer),
-                time: time.as_ref(),
+                answer: Some(answer),
+                time: time.as_ref().map(|t| &t.mean),
+                timing: time.as_ref(),
+                memory: memory.as_ref(),
             }
         }
-        fn from_baseline(Baseline { answer, time }: &'a Baseline) -> Self {
+        fn from_baseline(
+            Baseline {
+                answer,
+                time,
+                timing,
+                memory,
+            }: &'a Baseline,
+        ) -> Self {
             Self {
                 answer: answer.as_ref().map(String::as_str),
                 time: time.as_ref(),
+                timing: timing.as_ref(),
+                memory: memory.as_ref(),
             }
         }
     }
 
-    struct Day<'a, 'b>(
-        Option<&'a [Option<Result<Measurements, FailedMeasurements>>; 2]>,
-        Option<&'b [Option<Baseline>; 2]>,
-    );
-    impl Serialize for Day<'_, '_> {
+    struct Phases<'a, 'b>(Option<&'a InputMeasures>, Option<&'b InputBaseline>);
+    impl Serialize for Phases<'_, '_> {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: Serializer,
         {
-            serializer.collect_seq((0..2).map(|idx| -> Option<BorrowedBaseline<'_>> {
+            let parts = (0..2).map(|idx| -> Option<BorrowedBaseline<'_>> {
                 self.0
-                    .and_then(|r| r[idx].as_ref())
+                    .and_then(|r| r.parts[idx].as_ref())
                     .and_then(|r| r.as_ref().ok())
                     .map(BorrowedBaseline::from_measurement)
                     .or_else(|| {
                         // recover old baseline
                         self.1
-                            .and_then(|r| r[idx].as_ref())
+                            .and_then(|r| r.parts[idx].as_ref())
                             .map(BorrowedBaseline::from_baseline)
                     })
-            }))
+            });
+            let parse = self
+                .0
+                .and_then(|r| r.parse.as_ref())
+                .and_then(|r| r.as_ref().ok())
+                .map(|time| BorrowedBaseline {
+                    answer: None,
+                    time: time.as_ref().map(|t| &t.mean),
+                    timing: time.as_ref(),
+                    memory: None,
+                })
+                .or_else(|| {
+                    self.1
+                        .and_then(|r| r.parse.as_ref())
+                        .map(BorrowedBaseline::from_baseline)
+                });
+            // the parsing step is only present for the days that have one
+            match parse {
+                Some(parse) => serializer.collect_seq(parts.chain([Some(parse)])),
+                None => serializer.collect_seq(parts),
+            }
+        }
+    }
+
+    struct Day<'a, 'b>(
+        Option<&'a BTreeMap<String, InputMeasures>>,
+        Option<&'b PerInput<InputBaseline>>,
+    );
+    impl Serialize for Day<'_, '_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let inputs: Vec<_> = self
+                .0
+                .map(|m| m.keys())
+                .into_iter()
+                .flatten()
+                .chain(self.1.map(|b| b.0.keys()).into_iter().flatten())
+                .unique()
+                .collect();
+            let phases = |name: &str| {
+                Phases(
+                    self.0.and_then(|m| m.get(name)),
+                    self.1.and_then(|b| b.get(name)),
+                )
+            };
+            // same format of `PerInput`
+            if inputs == [DEFAULT_INPUT] {
+                phases(DEFAULT_INPUT).serialize(serializer)
+            } else {
+                serializer.collect_map(inputs.into_iter().map(|name| (name, phases(name))))
+            }
         }
     }
 
     struct Year<'a, 'b>(
-        Option<
-            &'a BTreeMap<
-                u8,
-                Result<[Option<Result<Measurements, FailedMeasurements>>; 2], io::Error>,
-            >,
-        >,
-        Option<&'b BTreeMap<u8, [Option<Baseline>; 2]>>,
+        Option<&'a BTreeMap<u8, DayMeasures>>,
+        Option<&'b BTreeMap<u8, PerInput<InputBaseline>>>,
     );
     impl Serialize for Year<'_, '_> {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -666,13 +1858,7 @@ fn dump_baseline(
         }
     }
 
-    struct NewBaselines<'a, 'b>(
-        &'a BTreeMap<
-            u16,
-            BTreeMap<u8, Result<[Option<Result<Measurements, FailedMeasurements>>; 2], io::Error>>,
-        >,
-        &'b Baselines,
-    );
+    struct NewBaselines<'a, 'b>(&'a Measures, &'b Baselines);
     impl Serialize for NewBaselines<'_, '_> {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
@@ -683,39 +1869,132 @@ fn dump_baseline(
         }
     }
 
-    let file = File::create(save_baseline).context("Cannot open save file")?;
-    serde_json::to_writer(file, &NewBaselines(measures, old_baselines))
-        .context("Cannot serialize measurements")?;
-    Ok(())
-}
-
-type Answers = BTreeMap<u16, BTreeMap<u8, [Option<String>; 2]>>;
-
-fn read_answers(file: impl AsRef<Path>) -> anyhow::Result<Answers> {
-    let file = File::open(file).context("Cannot open answer file")?;
-    serde_json::from_reader(file).context("Cannot parse answer file")
+    NewBaselines(measures, old_baselines)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Measurements {
     answer: String,
     multiline: bool,
-    time: Option<Duration>,
+    time: Option<TimeStats>,
+    /// Heap usage of the run computing the answer, if the counting allocator is installed
+    #[serde(default)]
+    memory: Option<MemoryStats>,
 }
 
-#[derive(Debug, Clone)]
-struct FailedMeasurements {
-    err: Option<Cow<'static, str>>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum FailedMeasurements {
+    /// The solution panicked
+    Panic {
+        err: Option<Cow<'static, str>>,
+        /// Source file, line and column of the panic
+        #[serde(default)]
+        location: Option<String>,
+        #[serde(default)]
+        backtrace: Option<String>,
+    },
+    /// The solution returned an error, with the messages of its chain of sources, outermost first
+    Error { chain: Vec<String> },
+    /// The isolated solution did not finish in time
+    Timeout(Duration),
+    /// The isolated solution overflowed its stack
+    StackOverflow,
+    /// The isolated solution ran out of memory, or was killed by the OOM killer
+    OutOfMemory,
+    /// The isolated solution aborted
+    Aborted,
+    /// The isolated solution was killed by a signal
+    Signal(i32),
+    /// The isolated solution exited without giving a result
+    Exited(Option<i32>),
+    /// The child process for the isolated solution could not be run
+    Spawn(String),
 }
 impl FailedMeasurements {
+    /// Broad class of the failure
+    fn class(&self) -> &'static str {
+        match self {
+            FailedMeasurements::Panic { .. } => "panic",
+            FailedMeasurements::Error { .. } => "error",
+            FailedMeasurements::Timeout(_) => "timeout",
+            FailedMeasurements::StackOverflow
+            | FailedMeasurements::OutOfMemory
+            | FailedMeasurements::Aborted
+            | FailedMeasurements::Signal(_)
+            | FailedMeasurements::Exited(_) => "crash",
+            FailedMeasurements::Spawn(_) => "spawn-error",
+        }
+    }
+
     fn to_html(&self, node: Node<'_>) -> Result<(), fmt::Error> {
-        let mut node = node.attr("class='part failed panic'");
-        if let Some(msg) = self.err.as_ref() {
-            writeln!(node, "{}", msg)?;
-        } else {
-            writeln!(node, "Solution panicked with unknown type")?;
+        let mut node = node.attr(&format!("class='part failed {}'", self.class()));
+        match self {
+            FailedMeasurements::Error { chain } => {
+                writeln!(node, "{}", chain[0])?;
+                if chain.len() > 1 {
+                    let mut causes = node.ul().attr("class='causes'");
+                    for cause in &chain[1..] {
+                        writeln!(causes.li(), "{cause}")?;
+                    }
+                }
+                Ok(())
+            }
+            FailedMeasurements::Panic {
+                backtrace: Some(backtrace),
+                ..
+            } => {
+                writeln!(node, "{self}")?;
+                let mut details = node.details().attr("class='backtrace'");
+                writeln!(details.summary(), "Backtrace")?;
+                writeln!(details.pre(), "{backtrace}")
+            }
+            _ => writeln!(node, "{self}"),
+        }
+    }
+
+    /// Backtrace of a panic, if captured
+    fn backtrace(&self) -> Option<&str> {
+        match self {
+            FailedMeasurements::Panic { backtrace, .. } => backtrace.as_deref(),
+            _ => None,
+        }
+    }
+}
+impl Display for FailedMeasurements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailedMeasurements::Panic { err, location, .. } => {
+                match location {
+                    Some(location) => write!(f, "Panicked at {location}")?,
+                    None => write!(f, "Panicked")?,
+                }
+                match err {
+                    Some(msg) => write!(f, ": {msg}"),
+                    None => Ok(()),
+                }
+            }
+            FailedMeasurements::Error { chain } => {
+                write!(f, "{}", chain[0])?;
+                for cause in &chain[1..] {
+                    write!(f, "\ncaused by: {cause}")?;
+                }
+                Ok(())
+            }
+            FailedMeasurements::Timeout(timeout) => write!(
+                f,
+                "Timed out after {}",
+                humantime::format_duration(*timeout)
+            ),
+            FailedMeasurements::StackOverflow => write!(f, "Stack overflow"),
+            FailedMeasurements::OutOfMemory => write!(f, "Out of memory"),
+            FailedMeasurements::Aborted => write!(f, "Aborted"),
+            FailedMeasurements::Signal(signal) => write!(f, "Killed by signal {signal}"),
+            FailedMeasurements::Exited(Some(code)) => {
+                write!(f, "Exited with code {code} without an answer")
+            }
+            FailedMeasurements::Exited(None) => write!(f, "Exited without an answer"),
+            FailedMeasurements::Spawn(err) => write!(f, "Cannot run isolated solution: {err}"),
         }
-        Ok(())
     }
 }
 
@@ -732,8 +2011,111 @@ where
         .env()
         .init()
         .unwrap();
-    if let Err(err) = main(build, Args::parse_from(args)) {
+    let command = config::apply(Args::command()).unwrap_or_else(|err| {
+        log::error!("Fatal error: {err:?}");
+        exit(1)
+    });
+    let args =
+        Args::from_arg_matches(&command.get_matches_from(args)).unwrap_or_else(|err| err.exit());
+    if let Err(err) = main(build, args) {
         log::error!("Fatal error: {err:?}");
         exit(1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_input_baseline() {
+        // written before days could have multiple inputs and a parsing step
+        let baselines: Baselines = serde_json::from_str(
+            r#"{"2015": {"1": [{"answer": "42", "time": {"secs": 0, "nanos": 1500}}, null]}}"#,
+        )
+        .unwrap();
+        let inputs = &baselines[&2015][&1];
+        let baseline = inputs.single().unwrap();
+        assert_eq!(
+            baseline.parts[0].as_ref().unwrap().answer.as_deref(),
+            Some("42")
+        );
+        assert_eq!(
+            baseline.phase(Phase::Part(1)).unwrap().time_stats(),
+            Some(TimeStats::single(Duration::from_nanos(1500)))
+        );
+        assert!(baseline.parts[1].is_none());
+        assert!(baseline.parse.is_none());
+        assert!(inputs.get(DEFAULT_INPUT).is_some());
+    }
+
+    #[test]
+    fn per_input_baseline() {
+        let baselines: Baselines = serde_json::from_str(
+            r#"{"2015": {"1": {
+                "default": [{"answer": "1"}, {"answer": "2"}, {}],
+                "large": [null, {"answer": "3"}]
+            }}}"#,
+        )
+        .unwrap();
+        let inputs = &baselines[&2015][&1];
+        assert!(inputs.single().is_none());
+        let default = inputs.get(DEFAULT_INPUT).unwrap();
+        assert!(default.parse.is_some());
+        assert_eq!(
+            default.phases().map(|(phase, _)| phase).collect_vec(),
+            [Phase::Parse, Phase::Part(1), Phase::Part(2)]
+        );
+        let large = inputs.get("large").unwrap();
+        assert!(large.parts[0].is_none());
+        assert_eq!(
+            large.parts[1].as_ref().unwrap().answer.as_deref(),
+            Some("3")
+        );
+        assert!(large.parse.is_none());
+    }
+
+    #[test]
+    fn examples_overrides_are_cleared() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static PARSED: AtomicUsize = AtomicUsize::new(0);
+        fn parse(input: &str) -> usize {
+            PARSED.fetch_add(1, Ordering::Relaxed);
+            input.len()
+        }
+        let mut lib = Library {
+            solutions: BTreeMap::new(),
+            filters: Rc::default(),
+        };
+        lib.add_year(2015, |year| {
+            year.add_day(1, |day| {
+                day.add_part_1(str::len as fn(&str) -> usize, false, false)
+                    .add_example("small", "abc", |example| {
+                        example
+                            .expect(1, 3)
+                            .override_part(1, PartFn::parsed(parse, |len: &usize| *len));
+                    });
+            });
+        });
+        let day = &lib.solutions[&2015].solutions[&1];
+        let part = day.examples[0].part(day, 0).unwrap();
+        let call = || part.fun.call("abc", &part.params).0;
+
+        assert_eq!(call(), Ok("3".to_owned()));
+        assert_eq!(call(), Ok("3".to_owned()));
+        assert_eq!(PARSED.load(Ordering::Relaxed), 1);
+        lib.clear_parsed();
+        assert_eq!(call(), Ok("3".to_owned()));
+        assert_eq!(PARSED.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn invalid_baseline() {
+        assert!(serde_json::from_str::<Baselines>(r#"{"2015": {"1": [null]}}"#).is_err());
+        assert!(
+            serde_json::from_str::<Baselines>(r#"{"2015": {"1": [null, null, null, null]}}"#)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Measurement of the heap usage of the solutions
+//!
+//! With the `memory` feature the runner installs a global allocator wrapping the system one, that
+//! counts the allocations of each thread. Only the thread running a solution is observed, so the
+//! allocations of the threads it spawns are not counted. Without the feature nothing is measured.
+
+use std::{
+    fmt::{self, Display},
+    ops::Add,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Heap usage of a single run of a solution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(crate) struct MemoryStats {
+    /// Maximum number of bytes in use at the same time, above the ones in use before the run
+    pub(crate) peak: u64,
+    /// Number of bytes allocated, including the ones already freed
+    pub(crate) total: u64,
+    /// Number of allocations, reallocations included
+    pub(crate) allocations: u64,
+}
+/// Statistics of runs happening one after the other, so the peak is the highest one
+impl Add for MemoryStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            peak: self.peak.max(rhs.peak),
+            total: self.total + rhs.total,
+            allocations: self.allocations + rhs.allocations,
+        }
+    }
+}
+
+/// Run a function, measuring its heap usage if the counting allocator is installed
+pub(crate) fn measure<T>(fun: impl FnOnce() -> T) -> (T, Option<MemoryStats>) {
+    #[cfg(feature = "memory")]
+    {
+        let (result, stats) = counting::measure(fun);
+        (result, Some(stats))
+    }
+    #[cfg(not(feature = "memory"))]
+    {
+        (fun(), None)
+    }
+}
+
+/// A number of bytes, shown with a binary unit
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Bytes(pub(crate) u64);
+impl Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return write!(f, "{}B", self.0);
+        }
+        let mut value = self.0 as f64 / 1024.;
+        let mut unit = 0;
+        while value >= 1024. && unit + 1 < UNITS.len() {
+            value /= 1024.;
+            unit += 1;
+        }
+        write!(f, "{value:.1}{}", UNITS[unit])
+    }
+}
+
+#[cfg(feature = "memory")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+
+    use super::MemoryStats;
+
+    /// Counters of the allocations of a thread
+    ///
+    /// The bytes in use can go below zero if the thread frees memory allocated by another one.
+    struct Counters {
+        current: Cell<i64>,
+        peak: Cell<i64>,
+        total: Cell<u64>,
+        allocations: Cell<u64>,
+    }
+
+    thread_local! {
+        // constant initialization without a destructor, so the allocator can use it at any time
+        static COUNTERS: Counters = const {
+            Counters {
+                current: Cell::new(0),
+                peak: Cell::new(0),
+                total: Cell::new(0),
+                allocations: Cell::new(0),
+            }
+        };
+    }
+
+    fn allocated(size: usize) {
+        let _ = COUNTERS.try_with(|c| {
+            let current = c.current.get() + size as i64;
+            c.current.set(current);
+            c.peak.set(c.peak.get().max(current));
+            c.total.set(c.total.get() + size as u64);
+            c.allocations.set(c.allocations.get() + 1);
+        });
+    }
+
+    fn freed(size: usize) {
+        let _ = COUNTERS.try_with(|c| c.current.set(c.current.get() - size as i64));
+    }
+
+    pub(super) fn measure<T>(fun: impl FnOnce() -> T) -> (T, MemoryStats) {
+        let (start, total, allocations) = COUNTERS.with(|c| {
+            let start = c.current.get();
+            c.peak.set(start);
+            (start, c.total.get(), c.allocations.get())
+        });
+        let result = fun();
+        let stats = COUNTERS.with(|c| MemoryStats {
+            peak: (c.peak.get() - start) as u64,
+            total: c.total.get() - total,
+            allocations: c.allocations.get() - allocations,
+        });
+        (result, stats)
+    }
+
+    /// The system allocator, counting the allocations
+    struct Counting;
+
+    // SAFETY: all the allocations are delegated to the system allocator
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            freed(layout.size());
+        }
+
+        /// Counted as a new allocation of the new size, freeing the old one
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                freed(layout.size());
+                allocated(new_size);
+            }
+            new
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+}
@@ -0,0 +1,194 @@
+//! Capture of the panics of the solutions
+//!
+//! On the first measurement a panic hook is installed, that records the message, location and
+//! backtrace of the panics happening while a thread runs a solution, instead of printing them.
+//! The other panics are left to the previous hook. The panics of plugins are raised again in the
+//! runner without going through the hook, so only their message is known.
+
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    panic::{self, PanicHookInfo, UnwindSafe},
+    sync::Once,
+};
+
+thread_local! {
+    /// If the thread is running a solution
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    /// The last panic of the solution run by the thread
+    static CAPTURED: RefCell<Option<Captured>> = const { RefCell::new(None) };
+}
+
+/// What is known of a panic
+#[derive(Debug, Clone)]
+pub(crate) struct Captured {
+    pub(crate) message: Option<Cow<'static, str>>,
+    /// Source file, line and column of the panic
+    pub(crate) location: Option<String>,
+    /// Frames from the panicking function to the runner calling the solution
+    pub(crate) backtrace: Option<String>,
+}
+
+/// Run a solution, capturing its panic
+pub(crate) fn catch<T>(fun: impl FnOnce() -> T + UnwindSafe) -> Result<T, Captured> {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CAPTURING.get() {
+                CAPTURED.set(Some(capture(info)));
+            } else {
+                previous(info)
+            }
+        }));
+    });
+
+    CAPTURED.take();
+    let capturing = CAPTURING.replace(true);
+    let result = panic::catch_unwind(fun);
+    CAPTURING.set(capturing);
+    result.map_err(|payload| {
+        CAPTURED.take().unwrap_or_else(|| Captured {
+            message: message(payload),
+            location: None,
+            backtrace: None,
+        })
+    })
+}
+
+fn capture(info: &PanicHookInfo<'_>) -> Captured {
+    Captured {
+        message: info.payload_as_str().map(|s| Cow::Owned(s.to_owned())),
+        location: info.location().map(ToString::to_string),
+        backtrace: Some(trim(&Backtrace::force_capture().to_string())),
+    }
+}
+
+/// The message of a panic raised without the hook
+fn message(payload: Box<dyn Any + Send>) -> Option<Cow<'static, str>> {
+    match payload.downcast::<String>() {
+        Ok(s) => Some(Cow::Owned(*s)),
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(s) => Some(Cow::Borrowed(*s)),
+            Err(_) => None,
+        },
+    }
+}
+
+/// Remove the frames of the panic machinery, and the ones calling the solution
+///
+/// The whole backtrace is kept if its frames cannot be found.
+fn trim(backtrace: &str) -> String {
+    // a frame starts with its number, followed by its source location if known
+    let mut frames: Vec<Vec<&str>> = vec![];
+    for line in backtrace.lines() {
+        let numbered = line
+            .trim_start()
+            .split_once(": ")
+            .is_some_and(|(n, _)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+        match frames.last_mut() {
+            Some(frame) if !numbered => frame.push(line),
+            _ => frames.push(vec![line]),
+        }
+    }
+    fn symbol<'a>(frame: &[&'a str]) -> Option<&'a str> {
+        frame[0].trim_start().split_once(": ").map(|(_, s)| s)
+    }
+    let end = frames
+        .iter()
+        .position(|f| {
+            symbol(f).is_some_and(|s| {
+                s.starts_with("std::panicking::try")
+                    || s.starts_with("std::panicking::catch_unwind")
+            })
+        })
+        .unwrap_or(frames.len());
+    let start = frames[..end]
+        .iter()
+        .rposition(|f| {
+            symbol(f).is_some_and(|s| {
+                s.starts_with("core::panicking::")
+                    || s.starts_with("std::panicking::")
+                    || s.contains("rust_begin_unwind")
+            })
+        })
+        .map_or(0, |i| i + 1);
+    if start >= end {
+        return backtrace.to_owned();
+    }
+    frames[start..end]
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Captured in a test calling `catch(|| part1(""))`, with the frames of the test harness cut
+    const SAMPLE: &str = r"   0: runner::panics::capture
+             at ./src/panics.rs:65:34
+   1: runner::panics::catch::<usize, runner::panics::tests::sample::{closure#0}>::{closure#0}::{closure#0}
+             at ./src/panics.rs:41:35
+   2: <alloc::boxed::Box<dyn for<'a, 'b> core::ops::function::Fn<(&'a std::panic::PanicHookInfo<'b>,), Output = ()> + core::marker::Sync + core::marker::Send> as core::ops::function::Fn<(&std::panic::PanicHookInfo,)>>::call
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/alloc/src/boxed.rs:2289:9
+   3: std::panicking::panic_with_hook
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/std/src/panicking.rs:833:13
+   4: std::panicking::panic_handler::{closure#0}
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/std/src/panicking.rs:691:13
+   5: std::sys::backtrace::__rust_end_short_backtrace::<std::panicking::panic_handler::{closure#0}, !>
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/std/src/sys/backtrace.rs:182:18
+   6: __rustc::rust_begin_unwind
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/std/src/panicking.rs:689:5
+   7: core::panicking::panic_fmt
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/core/src/panicking.rs:80:14
+   8: runner::panics::tests::part1
+             at ./src/panics.rs:132:34
+   9: runner::panics::tests::sample::{closure#0}
+             at ./src/panics.rs:134:36
+  10: std::panicking::catch_unwind::do_call::<runner::panics::tests::sample::{closure#0}, usize>
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/std/src/panicking.rs:581:40
+  11: __rust_try
+  12: std::panicking::catch_unwind::<usize, runner::panics::tests::sample::{closure#0}>
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/std/src/panicking.rs:544:19
+  13: std::panic::catch_unwind::<runner::panics::tests::sample::{closure#0}, usize>
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/std/src/panic.rs:359:14
+  14: runner::panics::catch::<usize, runner::panics::tests::sample::{closure#0}>
+             at ./src/panics.rs:50:18
+  15: runner::panics::tests::sample
+             at ./src/panics.rs:134:27
+  16: runner::panics::tests::sample::{closure#0}
+             at ./src/panics.rs:134:16
+  17: <runner::panics::tests::sample::{closure#0} as core::ops::function::FnOnce<()>>::call_once
+             at /rustc/e50aa6fba4e63ab34c72bf9acfd2c307c1155d1a/library/core/src/ops/function.rs:250:5";
+
+    #[test]
+    fn trims_the_runner_frames() {
+        let solution = [
+            "   8: runner::panics::tests::part1",
+            "             at ./src/panics.rs:132:34",
+            "   9: runner::panics::tests::sample::{closure#0}",
+            "             at ./src/panics.rs:134:36",
+        ];
+        assert_eq!(trim(SAMPLE), solution.join("\n"));
+
+        // without the frames of the runner calling the solution
+        let end = SAMPLE.find("  10: ").unwrap();
+        assert_eq!(trim(&SAMPLE[..end]), solution.join("\n"));
+        // without the frames of the panic machinery
+        let start = SAMPLE.find("   8: ").unwrap();
+        let end = SAMPLE.find("  10: ").unwrap();
+        assert_eq!(trim(&SAMPLE[start..end]), solution.join("\n"));
+
+        // kept whole when no frame is left
+        let unknown = "   0: <unknown>\n   1: <unknown>";
+        assert_eq!(trim(unknown), unknown);
+        let unresolved = "   0: std::panicking::catch_unwind\n   1: core::panicking::panic_fmt";
+        assert_eq!(trim(unresolved), unresolved);
+    }
+}
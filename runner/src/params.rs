@@ -0,0 +1,114 @@
+//! Parameters of the solutions, e.g. the size of a grid that is smaller on the examples
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Values of the parameters of a part, in the order they were declared
+///
+/// The values are kept as text, and parsed by the part before it is run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(Vec<(String, String)>);
+impl Params {
+    /// Value of a parameter
+    pub fn get<T>(&self, name: &str) -> Result<T, ParamError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some((_, value)) = self.0.iter().find(|(n, _)| n == name) else {
+            return Err(ParamError::Missing(name.to_owned()));
+        };
+        value.parse().map_err(|err: T::Err| ParamError::Invalid {
+            name: name.to_owned(),
+            value: value.clone(),
+            error: err.to_string(),
+        })
+    }
+
+    /// Set a parameter, keeping its position if it was already set
+    pub(crate) fn set(&mut self, name: &str, value: String) {
+        match self.0.iter_mut().find(|(n, _)| n == name) {
+            Some((_, old)) => *old = value,
+            None => self.0.push((name.to_owned(), value)),
+        }
+    }
+
+    /// Replace the values of the parameters also set in `overrides`
+    ///
+    /// The other parameters of `overrides` are not taken, as the part does not have them.
+    pub(crate) fn override_with(&mut self, overrides: &Params) {
+        for (name, value) in &mut self.0 {
+            if let Some((_, new)) = overrides.0.iter().find(|(n, _)| n == name) {
+                value.clone_from(new)
+            }
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+}
+/// The parameters as `name=value`, separated by commas
+impl Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a parameter cannot be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// The parameter has no value
+    Missing(String),
+    /// The value cannot be parsed as the type of the parameter
+    Invalid {
+        name: String,
+        value: String,
+        error: String,
+    },
+}
+impl Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Missing(name) => write!(f, "Missing parameter {name}"),
+            ParamError::Invalid { name, value, error } => {
+                write!(f, "Invalid value {value:?} of parameter {name}: {error}")
+            }
+        }
+    }
+}
+impl Error for ParamError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get() {
+        let mut params = Params::default();
+        params.set("width", "11".to_owned());
+        assert_eq!(params.get::<u8>("width"), Ok(11));
+        assert_eq!(
+            params.get::<u8>("height"),
+            Err(ParamError::Missing("height".to_owned()))
+        );
+        params.set("width", "-1".to_owned());
+        assert!(matches!(
+            params.get::<u8>("width"),
+            Err(ParamError::Invalid { value, .. }) if value == "-1"
+        ));
+    }
+}
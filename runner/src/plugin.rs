@@ -0,0 +1,749 @@
+//! Solutions loaded at runtime from plugins
+//!
+//! A solution calling [`export_plugin!`](crate::export_plugin) and built as a dynamic library,
+//! e.g. with `cargo rustc -p aoc_2024_14 --crate-type cdylib`, is a plugin. The runner loads the
+//! plugins given with `--plugins`, as files or directories, and the days they register replace
+//! the ones of the library. A day can then be rebuilt and rerun without relinking the library,
+//! and the watch mode rebuilds and reloads the plugins of the changed days.
+//!
+//! The plugin is not built together with the runner, so they only share the C ABI defined here.
+//! The plugin builds its own [`Library`] with its copy of the runner, and describes it to the
+//! [`Registrar`] of the runner loading it. The functions of the parts stay in the plugin, called
+//! through [`Thunk`]s and exchanging only strings, until the runner drops them. They are timed in
+//! the plugin, and their panics are caught there and raised again in the runner. Their heap usage
+//! is not measured, as the plugin allocates with its own global allocator. The inputs they parse
+//! also stay in the plugin, that passes their [`Cache`]s for the runner to clear them.
+
+use std::{
+    any::Any,
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    env::{
+        self,
+        consts::{DLL_EXTENSION, DLL_PREFIX},
+    },
+    ffi::{c_void, OsStr},
+    fs, io,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process, slice, str,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::{bail, Context};
+use itertools::Itertools;
+
+use crate::{answer::ParsedCache, Day, Example, Library, Params, Part, PartFn, Year};
+
+/// Version of the ABI, to change with any of the types below
+const ABI_VERSION: u32 = 4;
+
+/// Prefix of the entry point of the plugins, followed by the name of their crate
+const ENTRY_PREFIX: &str = "aoc_plugin_";
+
+/// Status returned by the entry point
+const OK: u32 = 0;
+const INCOMPATIBLE: u32 = 1;
+const PANICKED: u32 = 2;
+
+/// Phase of a registered part, the others being the part numbers
+const PARSE: u8 = 0;
+
+/// Kind of the outputs of a part
+const ANSWER: u8 = 0;
+const ERROR: u8 = 1;
+const PANIC: u8 = 2;
+
+/// A borrowed string, valid for the duration of the call it is passed to
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Str {
+    ptr: *const u8,
+    len: usize,
+}
+impl Str {
+    fn new(s: &str) -> Self {
+        Self {
+            ptr: s.as_ptr(),
+            len: s.len(),
+        }
+    }
+
+    /// # Safety
+    /// The string must still be borrowed.
+    unsafe fn as_str<'a>(self) -> &'a str {
+        str::from_utf8_unchecked(slice::from_raw_parts(self.ptr, self.len))
+    }
+}
+
+/// Where a part writes its outputs: an answer, the chain of an error or a panic message
+#[repr(C)]
+struct Sink {
+    ctx: *mut c_void,
+    push: extern "C" fn(ctx: *mut c_void, kind: u8, text: Str),
+}
+impl Sink {
+    fn push(&self, kind: u8, text: &str) {
+        (self.push)(self.ctx, kind, Str::new(text))
+    }
+}
+
+/// A function of the plugin, with the interface of [`PartFn`]
+///
+/// The durations are returned in nanoseconds. The function is owned by the runner, that frees it
+/// with `drop` before unloading the plugin.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Thunk {
+    fun: *const c_void,
+    signature: Str,
+    call: extern "C" fn(fun: *const c_void, input: Str, params: Str, sink: Sink) -> u64,
+    run: extern "C" fn(fun: *const c_void, input: Str, params: Str, iters: u32, sink: Sink) -> u64,
+    drop: extern "C" fn(fun: *const c_void),
+}
+
+/// The inputs parsed by the functions of a day of the plugin
+///
+/// The cache is owned by the runner, that clears it after each run and frees it with `drop`
+/// before unloading the plugin.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Cache {
+    cache: *const c_void,
+    clear: extern "C" fn(cache: *const c_void),
+    drop: extern "C" fn(cache: *const c_void),
+}
+
+/// The callbacks of the runner loading a plugin
+#[repr(C)]
+pub struct Registrar {
+    abi_version: u32,
+    ctx: *mut c_void,
+    add_part: extern "C" fn(
+        ctx: *mut c_void,
+        year: u16,
+        day: u8,
+        phase: u8,
+        fun: Thunk,
+        multiline: bool,
+        long_running: bool,
+    ),
+    set_part_timeout: extern "C" fn(ctx: *mut c_void, year: u16, day: u8, part: u8, secs: f64),
+    set_part_param:
+        extern "C" fn(ctx: *mut c_void, year: u16, day: u8, part: u8, name: Str, value: Str),
+    add_example: extern "C" fn(ctx: *mut c_void, year: u16, day: u8, name: Str, input: Str),
+    set_example_param:
+        extern "C" fn(ctx: *mut c_void, year: u16, day: u8, example: Str, name: Str, value: Str),
+    expect:
+        extern "C" fn(ctx: *mut c_void, year: u16, day: u8, example: Str, part: u8, answer: Str),
+    override_part:
+        extern "C" fn(ctx: *mut c_void, year: u16, day: u8, example: Str, part: u8, fun: Thunk),
+    set_source_dir: extern "C" fn(ctx: *mut c_void, year: u16, day: u8, dir: Str),
+    add_parsed: extern "C" fn(ctx: *mut c_void, year: u16, day: u8, cache: Cache),
+}
+
+/// Register the solutions built by `build` in the runner loading the plugin
+///
+/// This is called by the entry point generated by [`export_plugin!`](crate::export_plugin).
+pub fn export<F>(registrar: &Registrar, build: F) -> u32
+where
+    F: FnOnce(&mut Library),
+{
+    if registrar.abi_version != ABI_VERSION {
+        return INCOMPATIBLE;
+    }
+    // unwinding into the runner would abort it
+    let library = catch_unwind(AssertUnwindSafe(|| {
+        let mut library = Library {
+            solutions: BTreeMap::new(),
+            filters: Default::default(),
+        };
+        build(&mut library);
+        library
+    }));
+    let Ok(library) = library else {
+        return PANICKED;
+    };
+
+    let ctx = registrar.ctx;
+    for day in library
+        .solutions
+        .values()
+        .flat_map(|y| y.solutions.values())
+    {
+        let (y, d) = day.day;
+        if let Some(dir) = day.source_dir.as_deref().and_then(Path::to_str) {
+            (registrar.set_source_dir)(ctx, y, d, Str::new(dir))
+        }
+        for (phase, part) in day.phases() {
+            let phase = match phase {
+                crate::Phase::Parse => PARSE,
+                crate::Phase::Part(p) => p,
+            };
+            let signature = format!("{:?}", part.fun);
+            (registrar.add_part)(
+                ctx,
+                y,
+                d,
+                phase,
+                thunk(&part.fun, &signature),
+                part.multiline,
+                part.long_running,
+            );
+            if let Some(timeout) = part.timeout {
+                (registrar.set_part_timeout)(ctx, y, d, phase, timeout.as_secs_f64())
+            }
+            for (name, value) in part.params.iter() {
+                (registrar.set_part_param)(ctx, y, d, phase, Str::new(name), Str::new(value))
+            }
+        }
+        for example in &day.examples {
+            let name = Str::new(&example.name);
+            (registrar.add_example)(ctx, y, d, name, Str::new(&example.input));
+            for (param, value) in example.params.iter() {
+                (registrar.set_example_param)(ctx, y, d, name, Str::new(param), Str::new(value))
+            }
+            for (p, answer) in (1..).zip(&example.answers) {
+                let Some(answer) = answer else { continue };
+                (registrar.expect)(ctx, y, d, name, p, Str::new(answer));
+            }
+            for (p, fun) in (1..).zip(&example.funs) {
+                let Some(fun) = fun else { continue };
+                let signature = format!("{fun:?}");
+                (registrar.override_part)(ctx, y, d, name, p, thunk(fun, &signature));
+            }
+        }
+        for parsed in &day.parsed {
+            (registrar.add_parsed)(ctx, y, d, cache(parsed))
+        }
+    }
+    OK
+}
+
+/// Pass the inputs parsed by the functions of the plugin to the runner, that owns them from then on
+fn cache(parsed: &Arc<dyn ParsedCache>) -> Cache {
+    extern "C" fn clear(cache: *const c_void) {
+        // SAFETY: the cache is boxed by `cache` until it is dropped
+        let cache = unsafe { &*cache.cast::<Arc<dyn ParsedCache>>() };
+        // the parsed inputs are values of the solution, whose drop can panic
+        let _ = catch_unwind(|| cache.clear());
+    }
+    extern "C" fn drop(cache: *const c_void) {
+        // SAFETY: the cache is boxed by `cache`, and the runner drops it once
+        let cache = unsafe { Box::from_raw(cache.cast::<Arc<dyn ParsedCache>>().cast_mut()) };
+        let _ = catch_unwind(AssertUnwindSafe(|| std::mem::drop(cache)));
+    }
+
+    Cache {
+        cache: Box::into_raw(Box::new(parsed.clone())).cast_const().cast(),
+        clear,
+        drop,
+    }
+}
+
+/// Pass a function of the plugin to the runner, that owns it from then on
+fn thunk(fun: &PartFn, signature: &str) -> Thunk {
+    extern "C" fn call(fun: *const c_void, input: Str, params: Str, sink: Sink) -> u64 {
+        // SAFETY: the function is boxed by `thunk` until it is dropped, and the strings are
+        // borrowed by the caller
+        let (fun, input, params) = unsafe {
+            (
+                &*fun.cast::<PartFn>(),
+                input.as_str(),
+                decode_params(params.as_str()),
+            )
+        };
+        match catch_unwind(|| fun.call(input, &params)) {
+            Ok((outcome, elapsed, _)) => {
+                match outcome {
+                    Ok(answer) => sink.push(ANSWER, &answer),
+                    Err(chain) => chain.iter().for_each(|err| sink.push(ERROR, err)),
+                }
+                nanos(elapsed)
+            }
+            Err(payload) => {
+                sink.push(PANIC, panic_message(&*payload));
+                0
+            }
+        }
+    }
+    extern "C" fn run(fun: *const c_void, input: Str, params: Str, iters: u32, sink: Sink) -> u64 {
+        // SAFETY: the function is boxed by `thunk` until it is dropped, and the strings are
+        // borrowed by the caller
+        let (fun, input, params) = unsafe {
+            (
+                &*fun.cast::<PartFn>(),
+                input.as_str(),
+                decode_params(params.as_str()),
+            )
+        };
+        match catch_unwind(|| fun.run(input, &params, iters)) {
+            Ok(elapsed) => nanos(elapsed),
+            Err(payload) => {
+                sink.push(PANIC, panic_message(&*payload));
+                0
+            }
+        }
+    }
+
+    extern "C" fn drop(fun: *const c_void) {
+        // SAFETY: the function is boxed by `thunk`, and the runner drops it once
+        let fun = unsafe { Box::from_raw(fun.cast::<PartFn>().cast_mut()) };
+        // the parsed inputs it keeps are values of the solution, whose drop can panic
+        let _ = catch_unwind(AssertUnwindSafe(|| std::mem::drop(fun)));
+    }
+
+    Thunk {
+        fun: Box::into_raw(Box::new(fun.clone())).cast_const().cast(),
+        signature: Str::new(signature),
+        call,
+        run,
+        drop,
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// The message of a panic, empty if it has none
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| payload.downcast_ref::<&'static str>().copied())
+        .unwrap_or_default()
+}
+
+/// The parameters as `name\0value\0` pairs
+fn encode_params(params: &Params) -> String {
+    params
+        .iter()
+        .flat_map(|(name, value)| [name, "\0", value, "\0"])
+        .collect()
+}
+
+fn decode_params(encoded: &str) -> Params {
+    let mut params = Params::default();
+    for (name, value) in encoded.split_terminator('\0').tuples() {
+        params.set(name, value.to_owned())
+    }
+    params
+}
+
+/// Count of the loaded plugins, naming their copies
+static LOADED: AtomicUsize = AtomicUsize::new(0);
+
+/// Load the plugins, replacing the days of the library they register
+///
+/// The plugins are given as files, or as directories whose files that do not export the entry
+/// point of a plugin are skipped. The later plugins replace the days of the former ones.
+pub(crate) fn load(paths: &[PathBuf], library: &mut Library) -> anyhow::Result<()> {
+    for path in paths {
+        if !path.is_dir() {
+            load_plugin(path, true, library)?;
+            continue;
+        }
+        let mut files: Vec<_> = fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| Ok(entry?.path()))
+                    .collect::<io::Result<_>>()
+            })
+            .with_context(|| format!("Cannot read {}", path.display()))?;
+        files.sort();
+        for file in files {
+            if file.extension() == Some(OsStr::new(DLL_EXTENSION)) {
+                load_plugin(&file, false, library)?
+            }
+        }
+    }
+    // the plugins do not create empty days, as their parts can be filtered out
+    library.solutions.retain(|_, year| {
+        year.solutions.retain(|_, day| !day.is_empty());
+        !year.is_empty()
+    });
+    Ok(())
+}
+
+/// Load a plugin, named after its crate
+///
+/// If it is not `required`, a file without the entry point is skipped.
+fn load_plugin(path: &Path, required: bool, library: &mut Library) -> anyhow::Result<()> {
+    let name = path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .map(|name| name.strip_prefix(DLL_PREFIX).unwrap_or(name))
+        .with_context(|| format!("{} is not named after a crate", path.display()))?;
+    load_plugin_named(path, name, required, library)
+        .with_context(|| format!("Cannot load plugin {}", path.display()))
+}
+
+fn load_plugin_named(
+    path: &Path,
+    name: &str,
+    required: bool,
+    library: &mut Library,
+) -> anyhow::Result<()> {
+    // Loading a copy, so the plugin can be rebuilt while it is in use, and a rebuilt plugin is
+    // not confused with the one already loaded from the same path. On unix the copy can be
+    // removed right away.
+    let copy = env::temp_dir().join(format!(
+        "aoc-plugin-{}-{}-{}",
+        process::id(),
+        LOADED.fetch_add(1, Ordering::Relaxed),
+        path.file_name().unwrap().to_string_lossy()
+    ));
+    fs::copy(path, &copy).with_context(|| format!("Cannot copy it to {}", copy.display()))?;
+    // SAFETY: the initializers of a plugin are the ones of the runner and the solution
+    let plugin = unsafe { libloading::Library::new(&copy) };
+    let _ = fs::remove_file(&copy);
+    let plugin = Arc::new(plugin?);
+
+    // SAFETY: the entry point has this signature in all the plugins
+    let entry = unsafe {
+        plugin.get::<extern "C" fn(&Registrar) -> u32>(format!("{ENTRY_PREFIX}{name}").as_bytes())
+    };
+    let entry = match entry {
+        Ok(entry) => entry,
+        Err(err) if required => return Err(err).context("It is not a plugin"),
+        Err(_) => {
+            log::warn!("Skipping {}, that is not a plugin", path.display());
+            return Ok(());
+        }
+    };
+    let mut loading = Loading {
+        library,
+        plugin: plugin.clone(),
+        replaced: HashSet::new(),
+    };
+    let registrar = Registrar {
+        abi_version: ABI_VERSION,
+        ctx: (&mut loading as *mut Loading).cast(),
+        add_part,
+        set_part_timeout,
+        set_part_param,
+        add_example,
+        set_example_param,
+        expect,
+        override_part,
+        set_source_dir,
+        add_parsed,
+    };
+    match entry(&registrar) {
+        OK => (),
+        INCOMPATIBLE => bail!("It was built with another version of the runner"),
+        _ => bail!("It panicked while registering its solutions"),
+    }
+    if loading.replaced.is_empty() {
+        log::debug!("Plugin {name} registered no selected solution")
+    } else {
+        log::info!("Loaded plugin {name}");
+    }
+    Ok(())
+}
+
+/// State of the runner while a plugin registers its solutions
+struct Loading<'l> {
+    library: &'l mut Library,
+    plugin: Arc<libloading::Library>,
+    /// The days already replaced by the plugin
+    replaced: HashSet<(u16, u8)>,
+}
+impl Loading<'_> {
+    /// # Safety
+    /// `ctx` must be the one of the registrar.
+    unsafe fn from_ctx<'a>(ctx: *mut c_void) -> &'a mut Self {
+        &mut *ctx.cast::<Self>()
+    }
+
+    /// A day registered by the plugin, if it is selected
+    ///
+    /// On the first use the day of the library is replaced by an empty one.
+    fn day(&mut self, year: u16, day: u8) -> Option<&mut Day> {
+        let filters = &self.library.filters;
+        if !filters.accept_day(year, day) {
+            return None;
+        }
+        let days = &mut (self.library.solutions)
+            .entry(year)
+            .or_insert_with(|| Year {
+                year,
+                solutions: BTreeMap::new(),
+                filters: filters.clone(),
+            })
+            .solutions;
+        if self.replaced.insert((year, day)) {
+            days.remove(&day);
+        }
+        Some(days.entry(day).or_insert_with(|| Day {
+            day: (year, day),
+            parse: None,
+            parsed: vec![],
+            parts: [None, None],
+            examples: vec![],
+            filters: filters.clone(),
+            source_dir: None,
+        }))
+    }
+
+    /// An example registered by the plugin, if its day is selected
+    fn example(&mut self, year: u16, day: u8, name: &str) -> Option<&mut Example> {
+        self.day(year, day)?
+            .examples
+            .iter_mut()
+            .rfind(|example| example.name == name)
+    }
+
+    /// A function of the plugin, keeping it loaded until the function is dropped
+    fn part_fn(&self, thunk: Thunk) -> PartFn {
+        let foreign = Arc::new(Foreign {
+            thunk,
+            _plugin: self.plugin.clone(),
+        });
+        let run = foreign.clone();
+        PartFn::from_fns(
+            // SAFETY: the signature is borrowed during the registration
+            unsafe { thunk.signature.as_str() }.to_owned(),
+            move |input, params| {
+                let Thunk { fun, call, .. } = foreign.thunk;
+                let mut output = Output::default();
+                let params = encode_params(params);
+                let elapsed = call(fun, Str::new(input), Str::new(&params), output.sink());
+                (output.finish(), Duration::from_nanos(elapsed), None)
+            },
+            move |input, params, iters| {
+                let Thunk { fun, run, .. } = run.thunk;
+                let mut output = Output::default();
+                let params = encode_params(params);
+                let elapsed = run(
+                    fun,
+                    Str::new(input),
+                    Str::new(&params),
+                    iters,
+                    output.sink(),
+                );
+                let _ = output.finish();
+                Duration::from_nanos(elapsed)
+            },
+        )
+    }
+}
+
+/// A function of a loaded plugin, freed before the plugin can be unloaded
+struct Foreign {
+    thunk: Thunk,
+    _plugin: Arc<libloading::Library>,
+}
+impl Drop for Foreign {
+    fn drop(&mut self) {
+        // the plugin is still loaded, as the fields are dropped after
+        (self.thunk.drop)(self.thunk.fun)
+    }
+}
+// SAFETY: the functions of the parts can be called from any thread
+unsafe impl Send for Foreign {}
+unsafe impl Sync for Foreign {}
+
+/// The inputs parsed by a loaded plugin, freed before the plugin can be unloaded
+struct ForeignCache {
+    cache: Cache,
+    _plugin: Arc<libloading::Library>,
+}
+impl ParsedCache for ForeignCache {
+    fn clear(&self) {
+        (self.cache.clear)(self.cache.cache)
+    }
+}
+impl Drop for ForeignCache {
+    fn drop(&mut self) {
+        // the plugin is still loaded, as the fields are dropped after
+        (self.cache.drop)(self.cache.cache)
+    }
+}
+impl std::fmt::Debug for ForeignCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("inputs parsed by a plugin")
+    }
+}
+// SAFETY: the parsed inputs are shared by the parts, that can be called from any thread
+unsafe impl Send for ForeignCache {}
+unsafe impl Sync for ForeignCache {}
+
+/// The outputs of a function of a plugin
+#[derive(Default)]
+struct Output {
+    answer: Option<String>,
+    chain: Vec<String>,
+    panic: Option<String>,
+}
+impl Output {
+    fn sink(&mut self) -> Sink {
+        extern "C" fn push(ctx: *mut c_void, kind: u8, text: Str) {
+            // SAFETY: the output outlives the call the sink is passed to
+            let (output, text) = unsafe { (&mut *ctx.cast::<Output>(), text.as_str().to_owned()) };
+            match kind {
+                ANSWER => output.answer = Some(text),
+                ERROR => output.chain.push(text),
+                _ => output.panic = Some(text),
+            }
+        }
+        Sink {
+            ctx: (self as *mut Self).cast(),
+            push,
+        }
+    }
+
+    /// The outcome of the function, raising again its panic
+    fn finish(self) -> Result<String, Vec<String>> {
+        match self.panic {
+            Some(msg) if msg.is_empty() => resume_unwind(Box::new(())),
+            Some(msg) => resume_unwind(Box::new(msg)),
+            None if self.chain.is_empty() => Ok(self.answer.unwrap_or_default()),
+            None => Err(self.chain),
+        }
+    }
+}
+
+extern "C" fn add_part(
+    ctx: *mut c_void,
+    year: u16,
+    day: u8,
+    phase: u8,
+    fun: Thunk,
+    multiline: bool,
+    long_running: bool,
+) {
+    // SAFETY: called by the plugin with the context of the registrar
+    let loading = unsafe { Loading::from_ctx(ctx) };
+    let fun = loading.part_fn(fun);
+    let Some(day) = loading.day(year, day) else {
+        return;
+    };
+    match phase {
+        PARSE => {
+            day.parse = Some(Part {
+                fun,
+                multiline,
+                long_running,
+                timeout: None,
+                params: Params::default(),
+            })
+        }
+        1 => {
+            day.add_part_1(fun, multiline, long_running);
+        }
+        _ => {
+            day.add_part_2(fun, multiline, long_running);
+        }
+    }
+}
+
+extern "C" fn set_part_timeout(ctx: *mut c_void, year: u16, day: u8, part: u8, secs: f64) {
+    // SAFETY: called by the plugin with the context of the registrar
+    let loading = unsafe { Loading::from_ctx(ctx) };
+    if let Some(day) = loading.day(year, day).filter(|_| part != PARSE) {
+        day.set_part_timeout(part, Duration::from_secs_f64(secs));
+    }
+}
+
+extern "C" fn set_part_param(
+    ctx: *mut c_void,
+    year: u16,
+    day: u8,
+    part: u8,
+    name: Str,
+    value: Str,
+) {
+    // SAFETY: called by the plugin with the context of the registrar, and borrowed strings
+    let (loading, name, value) = unsafe { (Loading::from_ctx(ctx), name.as_str(), value.as_str()) };
+    if let Some(day) = loading.day(year, day).filter(|_| part != PARSE) {
+        day.set_part_param(part, name, value);
+    }
+}
+
+extern "C" fn add_example(ctx: *mut c_void, year: u16, day: u8, name: Str, input: Str) {
+    // SAFETY: called by the plugin with the context of the registrar, and borrowed strings
+    let (loading, name, input) = unsafe { (Loading::from_ctx(ctx), name.as_str(), input.as_str()) };
+    if let Some(day) = loading.day(year, day) {
+        day.examples.push(Example {
+            name: name.to_owned(),
+            // the input is copied, as it is in the memory of the plugin
+            input: Cow::Owned(input.to_owned()),
+            answers: [None, None],
+            funs: [None, None],
+            params: Params::default(),
+        })
+    }
+}
+
+extern "C" fn set_example_param(
+    ctx: *mut c_void,
+    year: u16,
+    day: u8,
+    example: Str,
+    name: Str,
+    value: Str,
+) {
+    // SAFETY: called by the plugin with the context of the registrar, and borrowed strings
+    let (loading, example, name, value) = unsafe {
+        (
+            Loading::from_ctx(ctx),
+            example.as_str(),
+            name.as_str(),
+            value.as_str(),
+        )
+    };
+    if let Some(example) = loading.example(year, day, example) {
+        example.set_param(name, value);
+    }
+}
+
+extern "C" fn expect(ctx: *mut c_void, year: u16, day: u8, example: Str, part: u8, answer: Str) {
+    // SAFETY: called by the plugin with the context of the registrar, and borrowed strings
+    let (loading, example, answer) =
+        unsafe { (Loading::from_ctx(ctx), example.as_str(), answer.as_str()) };
+    if let Some(example) = loading.example(year, day, example) {
+        example.expect(part, answer);
+    }
+}
+
+extern "C" fn override_part(
+    ctx: *mut c_void,
+    year: u16,
+    day: u8,
+    example: Str,
+    part: u8,
+    fun: Thunk,
+) {
+    // SAFETY: called by the plugin with the context of the registrar, and borrowed strings
+    let (loading, example) = unsafe { (Loading::from_ctx(ctx), example.as_str()) };
+    let fun = loading.part_fn(fun);
+    if let Some(example) = loading.example(year, day, example) {
+        example.override_part(part, fun);
+    }
+}
+
+extern "C" fn set_source_dir(ctx: *mut c_void, year: u16, day: u8, dir: Str) {
+    // SAFETY: called by the plugin with the context of the registrar, and a borrowed string
+    let (loading, dir) = unsafe { (Loading::from_ctx(ctx), dir.as_str()) };
+    if let Some(day) = loading.day(year, day) {
+        day.set_source_dir(dir);
+    }
+}
+
+extern "C" fn add_parsed(ctx: *mut c_void, year: u16, day: u8, cache: Cache) {
+    // SAFETY: called by the plugin with the context of the registrar
+    let loading = unsafe { Loading::from_ctx(ctx) };
+    let cache = Arc::new(ForeignCache {
+        cache,
+        _plugin: loading.plugin.clone(),
+    });
+    if let Some(day) = loading.day(year, day) {
+        day.parsed.push(cache);
+    }
+}
@@ -1,7 +1,7 @@
 use std::{
+    cmp::Ordering,
     collections::BTreeMap,
     fmt::{self, Write},
-    io,
     ops::Add,
     rc::Rc,
     time::Duration,
@@ -10,27 +10,43 @@ use std::{
 use either::Either::{self, Left, Right};
 use html_builder::{Html5, Node};
 
-use crate::{filters::Filters, Answers, Baseline, Baselines, FailedMeasurements, Measurements};
+use crate::{
+    answers::{Check, KnownAnswers},
+    bench::TimeStats,
+    filters::Filters,
+    history::Trends,
+    memory::{Bytes, MemoryStats},
+    Answers, Baseline, Baselines, DayMeasures, ExampleMeasure, ExampleMeasures, FailedMeasurements,
+    InputError, InputMeasures, Measurements, Measures, Params, PerInput, Phase, DEFAULT_INPUT,
+};
+
+mod json;
+mod junit;
+mod term;
+mod tui;
+
+pub(crate) use tui::{RowKey, TuiRow};
 
 #[derive(Debug, Clone)]
 pub struct Report {
     problems: Rc<Filters>,
+    examples: Vec<Example>,
     years: BTreeMap<u16, Year>,
     totals: Option<Stats>,
 }
 impl Report {
     pub(crate) fn new(
         problems: Rc<Filters>,
-        measures: BTreeMap<
-            u16,
-            BTreeMap<
-                u8,
-                Result<[Option<Result<Measurements, FailedMeasurements>>; 2], std::io::Error>,
-            >,
-        >,
+        measures: Measures,
+        examples: ExampleMeasures,
         answers: Answers,
-        baseline: Baselines,
+        baselines: Vec<(String, Baselines)>,
+        trends: &Trends,
     ) -> Self {
+        let baselines: Vec<_> = baselines
+            .into_iter()
+            .map(|(name, b)| (Rc::from(name), b))
+            .collect();
         let years: BTreeMap<_, _> = measures
             .into_iter()
             .map(|(y, sols)| {
@@ -40,24 +56,27 @@ impl Report {
                         y,
                         sols,
                         answers.get(&y).unwrap_or_else(|| {
-                            static DEFAULT: BTreeMap<
-                                u8,
-                                [std::option::Option<std::string::String>; 2],
-                            > = BTreeMap::new();
-                            &DEFAULT
-                        }),
-                        baseline.get(&y).unwrap_or_else(|| {
-                            static DEFAULT: BTreeMap<u8, [std::option::Option<Baseline>; 2]> =
+                            static DEFAULT: BTreeMap<u8, PerInput<[KnownAnswers; 2]>> =
                                 BTreeMap::new();
                             &DEFAULT
                         }),
+                        &baselines,
+                        trends,
                     ),
                 )
             })
             .collect();
-        let totals = years.values().filter_map(|y| y.totals).reduce(Stats::add);
+        let totals = years
+            .values()
+            .filter_map(|y| y.totals.clone())
+            .reduce(Stats::add);
+        let examples = examples
+            .into_iter()
+            .map(|((year, day, name), parts)| Example::new(year, day, name, parts))
+            .collect();
         Self {
             problems,
+            examples,
             years,
             totals,
         }
@@ -67,22 +86,61 @@ impl Report {
         self.problems.as_ref()
     }
 
+    /// Describe all the parts that regressed from a baseline
+    ///
+    /// `max_slowdown` is the fraction of the previous time a part can slow down before it counts.
+    pub(crate) fn regressions(&self, max_slowdown: f64) -> Vec<String> {
+        let mut regressions = vec![];
+        for (y, year) in &self.years {
+            for (d, day) in &year.days {
+                let Ok(day) = day else { continue };
+                for (name, input) in &day.inputs {
+                    let on_input = if day.has_named_inputs() {
+                        format!(" on input {name}")
+                    } else {
+                        String::new()
+                    };
+                    let parse =
+                        (input.parse.iter()).map(|p| (Phase::Parse, p.as_ref().map(|p| &p.stats)));
+                    let parts = (input.parts.iter().zip(1..)).filter_map(|(p, part)| {
+                        Some((Phase::Part(part), p.as_ref()?.as_ref().map(|p| &p.stats)))
+                    });
+                    for (phase, result) in parse.chain(parts) {
+                        let (stats, failure) = match result {
+                            Ok(stats) => (stats, None),
+                            Err(failure) => (&*failure.stats, Some(&failure.err)),
+                        };
+                        regressions.extend(stats.baselines.iter().filter_map(|c| {
+                            c.regression(stats, failure, max_slowdown)
+                                .map(|r| format!("{y} day {d} {phase}{on_input}: {r}"))
+                        }));
+                    }
+                }
+            }
+        }
+        regressions
+    }
+
     pub(crate) fn render(&self, node: &mut Node<'_>) -> Result<(), fmt::Error> {
         {
             let mut header = node.header();
             let mut title = header.h1().attr("class='report title'");
             writeln!(title, "Report for the query ")?;
-            writeln!(title.code(), "\"{}\"", &self.problems)?;
+            writeln!(title.code(), "\"{}\"", self.problems)?;
+        }
+
+        if !self.examples.is_empty() {
+            self.render_examples(node.div().attr("class='examples container'"))?;
         }
 
-        if let Some(totals) = self.totals {
+        if let Some(totals) = &self.totals {
             let mut table = node.table().attr("class='report totals container'");
             let mut head = table.thead();
             writeln!(head.tr().th().attr("colspan='2'"), "Combined totals")?;
             let mut r2 = head.tr();
             writeln!(r2.th(), "Time")?;
             writeln!(r2.th(), "Ok")?;
-            totals.to_html_table_cells(&mut table.tbody().tr(), 2, "report totals")?;
+            totals.to_html_table_cells(&mut table.tbody().tr(), 2, "report totals", &[])?;
         }
 
         let mut years = node.div().attr("class='years container'");
@@ -94,6 +152,120 @@ impl Report {
     }
 }
 
+#[derive(Debug, Clone)]
+struct Example {
+    year: u16,
+    day: u8,
+    name: String,
+    parts: [Option<ExamplePart>; 2],
+}
+impl Example {
+    fn new(year: u16, day: u8, name: String, parts: [Option<ExampleMeasure>; 2]) -> Self {
+        Self {
+            year,
+            day,
+            name,
+            parts: parts.map(|p| {
+                p.map(
+                    |ExampleMeasure {
+                         expected,
+                         params,
+                         result,
+                     }| ExamplePart {
+                        expected,
+                        params,
+                        answer: result.map(|m| m.answer),
+                    },
+                )
+            }),
+        }
+    }
+
+    fn passed(&self) -> bool {
+        self.parts.iter().flatten().all(ExamplePart::passed)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ExamplePart {
+    expected: String,
+    /// Parameters of the part on the example
+    params: Params,
+    answer: Result<String, FailedMeasurements>,
+}
+impl ExamplePart {
+    fn passed(&self) -> bool {
+        self.answer
+            .as_ref()
+            .is_ok_and(|answer| *answer == self.expected)
+    }
+
+    fn to_html(&self, node: Node<'_>) -> Result<(), fmt::Error> {
+        let answer = match &self.answer {
+            Ok(answer) => answer,
+            Err(err) => return err.to_html(node),
+        };
+        let mut node = node.attr(if self.passed() {
+            "class='example part answer correct'"
+        } else {
+            "class='example part answer wrong'"
+        });
+        if !self.params.is_empty() {
+            writeln!(node.small().attr("class='params'"), "{}", self.params)?;
+        }
+        if !self.passed() {
+            write!(node.small(), "expected")?;
+            writeln!(node.pre().code(), "{}", self.expected)?;
+            write!(node.small(), "got")?;
+        }
+        writeln!(node.pre().code(), "{answer}")
+    }
+}
+
+impl Report {
+    /// Number of passed examples, and the total
+    fn examples_summary(&self) -> (usize, usize) {
+        (
+            self.examples.iter().filter(|e| e.passed()).count(),
+            self.examples.len(),
+        )
+    }
+
+    fn render_examples(&self, mut node: Node<'_>) -> Result<(), fmt::Error> {
+        let (passed, total) = self.examples_summary();
+        writeln!(
+            node.h2().attr("class='examples title'"),
+            "Examples: {passed}/{total} passed"
+        )?;
+        let mut table = node.table().attr("class='examples container'");
+        {
+            let mut head = table.thead();
+            let mut head = head.tr();
+            for title in ["Year", "Day", "Example", "Part 1", "Part 2"] {
+                writeln!(head.th(), "{title}")?;
+            }
+        }
+        let mut body = table.tbody();
+        for example in &self.examples {
+            let mut row = body.tr().attr(if example.passed() {
+                "class='example passed'"
+            } else {
+                "class='example failed'"
+            });
+            writeln!(row.td(), "{}", example.year)?;
+            writeln!(row.td(), "{}", example.day)?;
+            writeln!(row.td(), "{}", example.name)?;
+            for part in &example.parts {
+                match part {
+                    Some(part) => part.to_html(row.td())?,
+                    None => writeln!(row.td().attr("class='part missing'"), "-")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Year {
     year: u16,
@@ -104,12 +276,10 @@ struct Year {
 impl Year {
     fn new(
         year: u16,
-        sols: BTreeMap<
-            u8,
-            Result<[Option<Result<Measurements, FailedMeasurements>>; 2], io::Error>,
-        >,
-        answers: &BTreeMap<u8, [Option<String>; 2]>,
-        baselines: &BTreeMap<u8, [Option<Baseline>; 2]>,
+        sols: BTreeMap<u8, DayMeasures>,
+        answers: &BTreeMap<u8, PerInput<[KnownAnswers; 2]>>,
+        baselines: &[(Rc<str>, Baselines)],
+        trends: &Trends,
     ) -> Self {
         assert!(year >= 2015);
 
@@ -124,8 +294,9 @@ impl Year {
                             year,
                             d,
                             meas,
-                            answers.get(&d).unwrap_or_else(|| &[None, None]),
-                            baselines.get(&d).unwrap_or_else(|| &[None, None]),
+                            answers.get(&d),
+                            baselines,
+                            trends,
                             |dest: MultilineDest, ans: String| {
                                 multilines.insert(dest, ans);
                             },
@@ -135,10 +306,17 @@ impl Year {
                 )
             })
             .collect();
-        let totals = days
+        let mut totals = days
             .values()
-            .filter_map(|d| d.as_ref().ok().map(|d| d.totals).flatten())
+            .filter_map(|d| d.as_ref().ok().and_then(|d| d.totals.clone()))
             .reduce(Stats::add);
+        // the days that were not run are not known to be correct, or are not if the input is bad
+        if let Some(totals) = &mut totals {
+            for err in days.values().filter_map(|d| d.as_ref().err()) {
+                let correct = (!err.is_skipped()).then_some(false);
+                totals.correct = all_correct(totals.correct, correct);
+            }
+        }
         Self {
             year,
             days,
@@ -147,16 +325,33 @@ impl Year {
         }
     }
 
+    /// Check if any of the days has a parsing step
+    fn has_parse(&self) -> bool {
+        self.days
+            .values()
+            .any(|d| d.as_ref().is_ok_and(Day::has_parse))
+    }
+
+    /// Content of a multiline answer
+    fn multiline(&self, mref: &MultilineRef) -> &str {
+        let MultilineRef(year, day, input, part) = mref.clone();
+        &self.multilines[&MultilineDest(year, day, input, part)]
+    }
+
     fn render(&self, node: Node<'_>) -> Result<(), fmt::Error> {
         let mut node = node.attr("class='year'");
 
-        writeln!(node.h2().attr("class='year title'"), "Year {}", &self.year)?;
+        writeln!(node.h2().attr("class='year title'"), "Year {}", self.year)?;
 
+        let parse = self.has_parse();
         let mut table = node.table().attr("class='days container'");
         {
             let mut head = table.thead();
             let mut r1 = head.tr();
             writeln!(r1.th().attr("rowspan='2'"), "Days")?;
+            if parse {
+                writeln!(r1.th().attr("rowspan='2'"), "Parse")?;
+            }
             writeln!(r1.th().attr("colspan='3'"), "Part 1")?;
             writeln!(r1.th().attr("colspan='3'"), "Part 2")?;
             writeln!(r1.th().attr("colspan='2'"), "Total")?;
@@ -173,11 +368,16 @@ impl Year {
             let mut body = table.tbody();
             for (d, day) in &self.days {
                 match day {
-                    Ok(day) => day.to_html(body.tr())?,
+                    Ok(day) => day.to_html(&mut body, parse)?,
                     Err(err) => {
-                        let mut row = body.tr().attr("class='day input-error'");
+                        let mut row = body.tr().attr(if err.is_skipped() {
+                            "class='day missing-input'"
+                        } else {
+                            "class='day input-error'"
+                        });
                         writeln!(row.th().attr("class='day number'"), "{}", d)?;
-                        err.to_html(row.td().attr("colspan='8'"))
+                        let colspan = if parse { 9 } else { 8 };
+                        err.to_html(row.td().attr(&format!("colspan='{colspan}'")))
                     }?,
                 }
             }
@@ -186,12 +386,14 @@ impl Year {
             let mut foot = table.tfoot();
             let mut row = foot.tr();
             writeln!(
-                row.th().attr("class='year totals'").attr("colspan='7'"),
+                row.th()
+                    .attr("class='year totals'")
+                    .attr(&format!("colspan='{}'", if parse { 8 } else { 7 })),
                 "Totals"
             )?;
 
-            if let Some(totals) = self.totals {
-                totals.to_html_table_cells(&mut row, 2, "year totals")?;
+            if let Some(totals) = &self.totals {
+                totals.to_html_table_cells(&mut row, 2, "year totals", &[])?;
             } else {
                 writeln!(
                     row.td()
@@ -212,12 +414,12 @@ impl Year {
             for (dest, value) in &self.multilines {
                 assert_eq!(dest.0, self.year);
                 let mut div = div.div().attr("class='multiline container'");
-                writeln!(
-                    dest.set_id(div.h4().attr("class='multiline title'")),
-                    "Day {} part {}",
-                    dest.1,
-                    dest.2
-                )?;
+                let mut title = dest.set_id(div.h4().attr("class='multiline title'"));
+                if dest.2 == DEFAULT_INPUT {
+                    writeln!(title, "Day {} part {}", dest.1, dest.3)?;
+                } else {
+                    writeln!(title, "Day {} part {} on input {}", dest.1, dest.3, dest.2)?;
+                }
                 // writing the content with raw streams to avoid formatting
                 let mut content = div.raw();
                 write!(content, "<pre class='multiline content'><code>")?;
@@ -235,91 +437,313 @@ impl Year {
 #[derive(Debug, Clone)]
 struct Day {
     day: u8,
-    parts: [Option<Result<Part, FailedMeasurements>>; 2],
+    /// Results on each input
+    inputs: BTreeMap<String, DayInput>,
+    /// Combined statistics of the parsing step over all the inputs, if the day has one
+    parse: Option<Stats>,
+    /// Combined statistics of each part over all the inputs
+    parts: [Option<Stats>; 2],
     totals: Option<Stats>,
 }
 impl Day {
     fn new(
         year: u16,
         day: u8,
-        meas: [Option<Result<Measurements, FailedMeasurements>>; 2],
-        answers: &[Option<String>; 2],
-        baselines: &[Option<Baseline>; 2],
+        meas: BTreeMap<String, InputMeasures>,
+        answers: Option<&PerInput<[KnownAnswers; 2]>>,
+        baselines: &[(Rc<str>, Baselines)],
+        trends: &Trends,
         mut multiline: impl FnMut(MultilineDest, String),
     ) -> Self {
         assert!(year >= 2015);
-        assert!(day >= 1 && day <= 25);
+        assert!((1..=25).contains(&day));
 
-        let mut f = |m: Option<Result<Measurements, FailedMeasurements>>, p: u8| {
-            m.map(|m| {
-                m.map(|m| {
-                    Part::new(
+        let inputs: BTreeMap<_, _> = meas
+            .into_iter()
+            .map(|(name, meas)| {
+                let input = DayInput::new(
+                    InputKey {
                         year,
                         day,
-                        p + 1,
-                        m,
-                        answers[p as usize].as_ref().map(String::as_str),
-                        baselines[p as usize].as_ref().unwrap_or_else(|| {
-                            static DEFAULT: Baseline = Baseline {
-                                answer: None,
-                                time: None,
-                            };
-                            &DEFAULT
-                        }),
-                        &mut multiline,
-                    )
+                        input: &name,
+                    },
+                    meas,
+                    answers.and_then(|a| a.get(&name)).unwrap_or_else(|| {
+                        static UNKNOWN: [KnownAnswers; 2] = [KnownAnswers::UNKNOWN; 2];
+                        &UNKNOWN
+                    }),
+                    baselines,
+                    trends,
+                    &mut multiline,
+                );
+                (name, input)
+            })
+            .collect();
+        // a failure on any input makes the whole part wrong
+        let combined = |stats: &dyn Fn(&DayInput) -> Option<&Stats>| {
+            inputs
+                .values()
+                .filter_map(stats)
+                .cloned()
+                .reduce(Stats::add)
+        };
+        let parse = combined(&DayInput::parse_stats);
+        let parts = [0, 1].map(|p| combined(&|input| input.part_stats(p)));
+        let totals = (parts.iter().flatten())
+            .chain(&parse)
+            .cloned()
+            .reduce(Stats::add);
+        Self {
+            day,
+            inputs,
+            parse,
+            parts,
+            totals,
+        }
+    }
+
+    /// Check if the day has inputs other than the default one
+    fn has_named_inputs(&self) -> bool {
+        self.inputs.keys().any(|name| name != DEFAULT_INPUT)
+    }
+
+    /// Check if the day has a parsing step
+    fn has_parse(&self) -> bool {
+        self.inputs.values().any(|input| input.parse.is_some())
+    }
+
+    /// Render the rows of the day, with a parse column if `parse` is set
+    fn to_html(&self, body: &mut Node<'_>, parse: bool) -> Result<(), fmt::Error> {
+        if !self.has_named_inputs() {
+            let mut row = body.tr().attr("class='day'");
+            writeln!(row.th().attr("class='day number'"), "{}", self.day)?;
+            if let Some(input) = self.inputs.get(DEFAULT_INPUT) {
+                input.to_html_table_cells(&mut row, "day", parse)?;
+            }
+            return Ok(());
+        }
+
+        // a row with the aggregates, followed by a row for each input
+        let mut row = body.tr().attr("class='day aggregate'");
+        writeln!(row.th().attr("class='day number'"), "{}", self.day)?;
+        if parse {
+            match &self.parse {
+                Some(stats) => stats.to_html_table_cells(&mut row, 1, "parse", &[])?,
+                None => writeln!(row.td().attr("class='parse missing'"), "-")?,
+            }
+        }
+        for (stats, part) in self.parts.iter().zip(["first", "second"]) {
+            match stats {
+                Some(stats) => {
+                    writeln!(
+                        row.td()
+                            .attr(&format!("class='part {part} answer aggregate'")),
+                        "{} inputs",
+                        self.inputs.len()
+                    )?;
+                    stats.to_html_table_cells(&mut row, 2, &format!("part {part}"), &[])?;
+                }
+                None => {
+                    writeln!(
+                        row.td().attr("colspan='3'").attr("class='part missing'"),
+                        "-"
+                    )?;
+                }
+            }
+        }
+        totals_to_html(&mut row, self.totals.as_ref(), "day")?;
+
+        for (name, input) in &self.inputs {
+            let mut row = body.tr().attr("class='day input'");
+            writeln!(row.th().attr("class='day input-name'"), "{name}")?;
+            input.to_html_table_cells(&mut row, "input", parse)?;
+        }
+        Ok(())
+    }
+}
+
+/// Day and input some results are for
+#[derive(Debug, Clone, Copy)]
+struct InputKey<'a> {
+    year: u16,
+    day: u8,
+    input: &'a str,
+}
+
+/// Results of a day on a single input
+#[derive(Debug, Clone)]
+struct DayInput {
+    parse: Option<Result<Parse, Failure>>,
+    parts: [Option<Result<Part, Failure>>; 2],
+    totals: Option<Stats>,
+}
+impl DayInput {
+    fn new(
+        key: InputKey<'_>,
+        meas: InputMeasures,
+        answers: &[KnownAnswers; 2],
+        baselines: &[(Rc<str>, Baselines)],
+        trends: &Trends,
+        mut multiline: impl FnMut(MultilineDest, String),
+    ) -> Self {
+        let InputKey { year, day, input } = key;
+        let baselines = |phase| {
+            baselines
+                .iter()
+                .map(|(name, b)| {
+                    let baseline = b
+                        .get(&year)
+                        .and_then(|y| y.get(&day))
+                        .and_then(|d| d.get(input))
+                        .and_then(|i| i.phase(phase));
+                    (name.clone(), baseline)
                 })
+                .collect::<Vec<_>>()
+        };
+        let trend = |phase| {
+            trends
+                .get(&(year, day, input.to_owned(), phase))
+                .map_or(&[][..], Vec::as_slice)
+        };
+        let mut f = |m: Option<Result<Measurements, FailedMeasurements>>, p: u8| {
+            let known = &answers[p as usize];
+            let baselines = baselines(Phase::Part(p + 1));
+            m.map(|m| match m {
+                Ok(m) => Ok(Part::new(
+                    key,
+                    p + 1,
+                    m,
+                    known,
+                    &baselines,
+                    trend(Phase::Part(p + 1)),
+                    &mut multiline,
+                )),
+                Err(err) => Err(Failure {
+                    err,
+                    stats: Box::new(Stats::failed(Comparison::of_part(known, &baselines))),
+                }),
             })
         };
-        let [p1, p2] = meas;
+        let [p1, p2] = meas.parts;
         let parts = [f(p1, 0), f(p2, 1)];
-        let totals = parts
-            .iter()
-            .filter_map(|p| p.as_ref().and_then(|r| r.as_ref().ok()).map(|p| p.stats))
+        let parse = meas.parse.map(|m| {
+            let baselines = baselines(Phase::Parse);
+            match m {
+                Ok(time) => Ok(Parse {
+                    stats: Stats::parse(time, &baselines),
+                    trend: trend(Phase::Parse).to_vec(),
+                }),
+                Err(err) => Err(Failure {
+                    err,
+                    stats: Box::new(Stats::failed(Comparison::of_parse(&baselines))),
+                }),
+            }
+        });
+        let mut input = Self {
+            parse,
+            parts,
+            totals: None,
+        };
+        // a failed phase makes the totals wrong, and their time unknown
+        input.totals = (0..2)
+            .filter_map(|p| input.part_stats(p))
+            .chain(input.parse_stats())
+            .cloned()
             .reduce(Stats::add);
-        Self { day, parts, totals }
+        input
     }
 
-    fn to_html(&self, node: Node<'_>) -> Result<(), fmt::Error> {
-        let mut node = node.attr("class='day'");
-        writeln!(node.th().attr("class='day number'"), "{}", self.day)?;
+    /// Statistics of the parsing step, whether it failed or not
+    fn parse_stats(&self) -> Option<&Stats> {
+        self.parse.as_ref().map(|parse| match parse {
+            Ok(parse) => &parse.stats,
+            Err(failure) => &*failure.stats,
+        })
+    }
 
+    /// Statistics of a part, from 0, whether it failed or not
+    fn part_stats(&self, p: usize) -> Option<&Stats> {
+        self.parts[p].as_ref().map(|part| match part {
+            Ok(part) => &part.stats,
+            Err(failure) => &*failure.stats,
+        })
+    }
+
+    fn to_html_table_cells(
+        &self,
+        row: &mut Node<'_>,
+        totals_class: &str,
+        parse: bool,
+    ) -> Result<(), fmt::Error> {
+        if parse {
+            match &self.parse {
+                Some(Ok(parse)) => {
+                    parse
+                        .stats
+                        .to_html_table_cells(row, 1, "parse", &parse.trend)?
+                }
+                Some(Err(Failure { err, .. })) => err.to_html(row.td())?,
+                None => writeln!(row.td().attr("class='parse missing'"), "-")?,
+            }
+        }
         for part in &self.parts {
             match part {
-                Some(Ok(part)) => part.to_html_table_cells(&mut node, 3)?,
-                Some(Err(err)) => {
-                    err.to_html(node.td().attr("colspan='3'"))?;
+                Some(Ok(part)) => part.to_html_table_cells(row, 3)?,
+                Some(Err(Failure { err, .. })) => {
+                    err.to_html(row.td().attr("colspan='3'"))?;
                 }
                 None => {
                     writeln!(
-                        node.td().attr("colspan='3'").attr("class='part missing'"),
+                        row.td().attr("colspan='3'").attr("class='part missing'"),
                         "-"
                     )?;
                 }
             }
         }
+        totals_to_html(row, self.totals.as_ref(), totals_class)
+    }
+}
 
-        if let Some(totals) = self.totals {
-            totals.to_html_table_cells(&mut node, 2, "day totals")?;
-        } else {
-            writeln!(
-                node.td()
-                    .attr("colspan='2'")
-                    .attr("class='day totals missing'"),
-                "-"
-            )?;
-        }
-
-        Ok(())
+fn totals_to_html(
+    row: &mut Node<'_>,
+    totals: Option<&Stats>,
+    class: &str,
+) -> Result<(), fmt::Error> {
+    if let Some(totals) = totals {
+        totals.to_html_table_cells(row, 2, &format!("{class} totals"), &[])
+    } else {
+        writeln!(
+            row.td()
+                .attr("colspan='2'")
+                .attr(&format!("class='{class} totals missing'")),
+            "-"
+        )
     }
 }
 
 #[derive(Debug, Clone)]
 struct LoadInputError {
-    err: Rc<io::Error>,
+    err: Rc<InputError>,
 }
 impl LoadInputError {
+    /// If the day has no input yet, and is skipped instead of failing
+    fn is_skipped(&self) -> bool {
+        self.err.is_skipped()
+    }
+
+    /// Why the day is skipped
+    fn skipped_label(&self) -> &'static str {
+        match *self.err {
+            InputError::Empty(_) => "Empty input",
+            _ => "No input",
+        }
+    }
+
     fn to_html(&self, node: Node<'_>) -> Result<(), fmt::Error> {
+        if self.is_skipped() {
+            let mut node = node.attr("class='day missing'");
+            return writeln!(node, "{}", self.skipped_label());
+        }
         let mut node = node.attr("class='day input-error'");
         writeln!(node, "Error in reading input: {}", self.err)
     }
@@ -329,38 +753,39 @@ impl LoadInputError {
 struct Part {
     part: u8,
     answer: Either<String, MultilineRef>,
+    expected: Option<String>,
+    /// Why the answer is certainly wrong, when the correct one is unknown
+    rejection: Option<String>,
     stats: Stats,
+    /// Running times of the previous runs, oldest first
+    trend: Vec<Duration>,
 }
 impl Part {
     fn new(
-        year: u16,
-        day: u8,
+        InputKey { year, day, input }: InputKey<'_>,
         part: u8,
         Measurements {
             answer,
             time,
+            memory,
             multiline,
         }: Measurements,
-        correct_answer: Option<&str>,
-        Baseline {
-            time: prev_time,
-            answer: prev_answer,
-        }: &Baseline,
+        known: &KnownAnswers,
+        baselines: &[(Rc<str>, Option<&Baseline>)],
+        trend: &[Duration],
         mut multiline_fun: impl FnMut(MultilineDest, String),
     ) -> Self {
         assert!(year >= 2015);
-        assert!(day >= 1 && day <= 25);
+        assert!((1..=25).contains(&day));
         assert!(part == 1 || part == 2);
 
-        let stats = Stats::new(
-            &answer,
-            prev_answer.as_ref().map(String::as_str),
-            correct_answer,
-            time,
-            *prev_time,
-        );
+        let rejection = match known.check(&answer) {
+            Check::Rejected(reason) => Some(reason),
+            _ => None,
+        };
+        let stats = Stats::new(&answer, known, time, memory, baselines);
         let answer = if multiline {
-            let (mref, mdest) = multiline_pairs(year, day, part);
+            let (mref, mdest) = multiline_pairs(year, day, input, part);
             multiline_fun(mdest, answer);
             Right(mref)
         } else {
@@ -369,7 +794,10 @@ impl Part {
         Self {
             part,
             answer,
+            expected: known.correct.clone(),
+            rejection,
             stats,
+            trend: trend.to_vec(),
         }
     }
 
@@ -385,55 +813,103 @@ impl Part {
             2 => "second",
             _ => unreachable!(),
         };
-        match &self.answer {
-            Left(s) => writeln!(
-                row.td().attr(&format!(
+        let mut td = match &self.answer {
+            Left(s) => {
+                let mut td = row.td().attr(&format!(
                     "class='part {part} answer {correctedness_class} no-multiline'"
-                )),
-                "{}",
-                s
-            )?,
-            Right(mref) => mref.to_html(row.td().attr(&format!(
-                "class='part {part} answer {correctedness_class} multiline'"
-            )))?,
+                ));
+                writeln!(td, "{}", s)?;
+                td
+            }
+            Right(mref) => {
+                let mut td = row.td().attr(&format!(
+                    "class='part {part} answer {correctedness_class} multiline'"
+                ));
+                mref.to_html(td.span())?;
+                td
+            }
+        };
+        if let Some(rejection) = &self.rejection {
+            td.br();
+            writeln!(td.small().attr("class='rejection'"), "{rejection}")?;
         }
         self.stats
-            .to_html_table_cells(row, 2, &format!("part {part}"))?;
+            .to_html_table_cells(row, 2, &format!("part {part}"), &self.trend)?;
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Results of the parsing step of a day on an input
+#[derive(Debug, Clone)]
+struct Parse {
+    stats: Stats,
+    /// Running times of the previous runs, oldest first
+    trend: Vec<Duration>,
+}
+/// A phase that failed, with its comparisons with the baselines
+#[derive(Debug, Clone)]
+struct Failure {
+    err: FailedMeasurements,
+    stats: Box<Stats>,
+}
+
+#[derive(Debug, Clone)]
 struct Stats {
     correct: Option<bool>,
-    was_correct: Option<bool>,
-
-    time: Option<Duration>,
-    previous_time: Option<Duration>,
+    time: Option<TimeStats>,
+    /// Heap usage, for sums the one of the parts where it was measured
+    memory: Option<MemoryStats>,
+    /// Comparisons with each baseline, in the same order for all the stats of a report
+    baselines: Vec<Comparison>,
 }
 impl Stats {
     fn new(
         answer: &str,
-        prev_answer: Option<&str>,
-        correct_answer: Option<&str>,
-        time: Option<Duration>,
-        prev_time: Option<Duration>,
+        known: &KnownAnswers,
+        time: Option<TimeStats>,
+        memory: Option<MemoryStats>,
+        baselines: &[(Rc<str>, Option<&Baseline>)],
     ) -> Self {
         Self {
-            correct: correct_answer.map(|ca| answer == ca),
-            was_correct: correct_answer.and_then(|ca| prev_answer.map(|pa| ca == pa)),
+            correct: known.check(answer).correct(),
             time,
-            previous_time: prev_time,
+            memory,
+            baselines: Comparison::of_part(known, baselines),
+        }
+    }
+
+    /// Statistics of a parsing step
+    ///
+    /// Parsing has no answer, so a parsing step that finished is always correct. Its heap usage is
+    /// not reported.
+    fn parse(time: Option<TimeStats>, baselines: &[(Rc<str>, Option<&Baseline>)]) -> Self {
+        Self {
+            correct: Some(true),
+            time,
+            memory: None,
+            baselines: Comparison::of_parse(baselines),
+        }
+    }
+
+    /// Statistics of a phase that failed, that is wrong and was not measured
+    fn failed(baselines: Vec<Comparison>) -> Self {
+        Self {
+            correct: Some(false),
+            time: None,
+            memory: None,
+            baselines,
         }
     }
 
+    /// Time and correctedness cells, or only the time one if `cells` is 1
     fn to_html_table_cells(
         &self,
         row: &mut Node<'_>,
         cells: usize,
         additional_td_classes: &str,
+        trend: &[Duration],
     ) -> Result<(), fmt::Error> {
-        assert_eq!(cells, 2);
+        assert!(cells == 1 || cells == 2);
         let correctedness_class = match self.correct {
             Some(true) => "correct",
             Some(false) => "wrong",
@@ -449,28 +925,78 @@ impl Stats {
                 }
             ));
             if let Some(time) = self.time {
-                writeln!(time_td, "{}", humantime::format_duration(time))?;
-                if let Some(previous_time) = self.previous_time {
+                writeln!(time_td, "{}", humantime::format_duration(time.mean))?;
+                if time.samples > 1 {
                     time_td.br();
-                    if previous_time <= time {
-                        writeln!(
-                            time_td.em().attr("class='time-diff slower'"),
-                            "[+ {}]",
-                            humantime::format_duration(time - previous_time)
-                        )?;
+                    writeln!(
+                        time_td.small().attr("class='time-stats'").attr(&format!(
+                            "title='min {} / median {} / p95 {} over {} samples'",
+                            humantime::format_duration(time.min),
+                            humantime::format_duration(time.median),
+                            humantime::format_duration(time.p95),
+                            time.samples
+                        )),
+                        "± {}",
+                        humantime::format_duration(time.stddev)
+                    )?;
+                }
+                let labelled = self.baselines.len() > 1;
+                for Comparison {
+                    name,
+                    previous_time,
+                    ..
+                } in &self.baselines
+                {
+                    let Some(previous_time) = previous_time else {
+                        continue;
+                    };
+                    time_td.br();
+                    let significance = if time.differs_from(previous_time) {
+                        ""
                     } else {
-                        writeln!(
-                            time_td.em().attr("class='time-diff faster'"),
-                            "[- {}]",
-                            humantime::format_duration(previous_time - time)
-                        )?;
-                    }
+                        " insignificant"
+                    };
+                    let label = if labelled {
+                        format!("{name}: ")
+                    } else {
+                        String::new()
+                    };
+                    let (direction, sign, diff) = if previous_time.mean <= time.mean {
+                        ("slower", "+", time.mean - previous_time.mean)
+                    } else {
+                        ("faster", "-", previous_time.mean - time.mean)
+                    };
+                    writeln!(
+                        time_td.em().attr(&format!(
+                            "class='time-diff {direction}{significance}' title='Compared to {name}'"
+                        )),
+                        "{label}[{sign} {}]",
+                        humantime::format_duration(diff)
+                    )?;
+                }
+                if !trend.is_empty() {
+                    time_td.br();
+                    let points: Vec<_> = trend.iter().copied().chain([time.mean]).collect();
+                    writeln!(
+                        time_td.span().attr("class='sparkline'").attr(&format!(
+                            "title='Last {} runs, from {} to {}'",
+                            points.len(),
+                            humantime::format_duration(*points.iter().min().unwrap()),
+                            humantime::format_duration(*points.iter().max().unwrap())
+                        )),
+                        "{}",
+                        sparkline(&points)
+                    )?;
                 }
             } else {
                 writeln!(time_td, "-")?;
             }
+            if let Some(memory) = self.memory {
+                time_td.br();
+                self.memory_to_html(time_td.small(), memory)?;
+            }
         }
-        {
+        if cells == 2 {
             let mut check = row
                 .td()
                 .attr(&format!(
@@ -483,7 +1009,7 @@ impl Stats {
                 } else {
                     write!(check, "&#x2718;")?;
                 }
-                if let Some(was_correct) = self.was_correct {
+                for was_correct in self.baselines.iter().filter_map(|c| c.was_correct) {
                     if was_correct != correct {
                         if was_correct {
                             write!(check, "(&#x2714;)")?;
@@ -502,33 +1028,183 @@ impl Stats {
     }
 }
 
+impl Stats {
+    /// Peak heap usage, with the totals in the title and the differences with the baselines
+    fn memory_to_html(&self, node: Node<'_>, memory: MemoryStats) -> Result<(), fmt::Error> {
+        let mut node = node.attr("class='memory'").attr(&format!(
+            "title='{} allocated in {} allocations'",
+            Bytes(memory.total),
+            memory.allocations
+        ));
+        write!(node, "peak {}", Bytes(memory.peak))?;
+        let labelled = self.baselines.len() > 1;
+        for Comparison {
+            name,
+            previous_memory,
+            ..
+        } in &self.baselines
+        {
+            let Some(previous) = previous_memory else {
+                continue;
+            };
+            let label = if labelled {
+                format!("{name}: ")
+            } else {
+                String::new()
+            };
+            let (direction, sign, diff) = match previous.peak.cmp(&memory.peak) {
+                Ordering::Less => ("more", "+", memory.peak - previous.peak),
+                Ordering::Equal => ("same", "+", 0),
+                Ordering::Greater => ("less", "-", previous.peak - memory.peak),
+            };
+            write!(
+                node.em().attr(&format!(
+                    "class='memory-diff {direction}' title='Compared to {name}: {} allocated in {} \
+                     allocations'",
+                    Bytes(previous.total),
+                    previous.allocations
+                )),
+                " {label}[{sign} {}]",
+                Bytes(diff)
+            )?;
+        }
+        writeln!(node)
+    }
+}
+
+/// Correctedness of a sum, wrong if any of its terms is
+fn all_correct(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
 impl Add for Stats {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
         Self {
-            correct: self.correct.map(|s| rhs.correct.map(|r| s && r)).flatten(),
-            was_correct: self
-                .was_correct
-                .map(|s| rhs.was_correct.map(|r| s && r))
-                .flatten(),
-            time: self.time.map(|s| rhs.time.map(|r| s + r)).flatten(),
+            correct: all_correct(self.correct, rhs.correct),
+            time: self.time.and_then(|s| rhs.time.map(|r| s + r)),
+            // the parsing steps and the plugins have no heap usage
+            memory: match (self.memory, rhs.memory) {
+                (Some(s), Some(r)) => Some(s + r),
+                (s, r) => s.or(r),
+            },
+            baselines: self
+                .baselines
+                .into_iter()
+                .zip(rhs.baselines)
+                .map(|(s, r)| s + r)
+                .collect(),
+        }
+    }
+}
+
+/// Comparison of a part, or a sum of them, with a baseline
+#[derive(Debug, Clone)]
+struct Comparison {
+    name: Rc<str>,
+    was_correct: Option<bool>,
+    previous_time: Option<TimeStats>,
+    previous_memory: Option<MemoryStats>,
+}
+impl Comparison {
+    /// Comparisons of a part with the baselines
+    fn of_part(known: &KnownAnswers, baselines: &[(Rc<str>, Option<&Baseline>)]) -> Vec<Self> {
+        baselines
+            .iter()
+            .map(|(name, baseline)| Comparison {
+                name: name.clone(),
+                was_correct: baseline
+                    .and_then(|b| b.answer.as_deref())
+                    .and_then(|pa| known.check(pa).correct()),
+                previous_time: baseline.and_then(Baseline::time_stats),
+                previous_memory: baseline.and_then(|b| b.memory),
+            })
+            .collect()
+    }
+
+    /// Comparisons of a parsing step with the baselines, where it was correct if it finished
+    fn of_parse(baselines: &[(Rc<str>, Option<&Baseline>)]) -> Vec<Self> {
+        baselines
+            .iter()
+            .map(|(name, baseline)| Comparison {
+                name: name.clone(),
+                was_correct: baseline.map(|_| true),
+                previous_time: baseline.and_then(Baseline::time_stats),
+                previous_memory: None,
+            })
+            .collect()
+    }
+
+    /// Describe how the stats regressed from this baseline, if they did
+    ///
+    /// A regression is a correct answer becoming wrong or failing, or a significant slowdown of
+    /// more than `max_slowdown`, as a fraction of the previous time.
+    fn regression(
+        &self,
+        stats: &Stats,
+        failure: Option<&FailedMeasurements>,
+        max_slowdown: f64,
+    ) -> Option<String> {
+        if let (Some(true), Some(err)) = (self.was_correct, failure) {
+            let err = err.to_string();
+            return Some(format!(
+                "{} (was correct in {})",
+                err.lines().next().unwrap_or_default(),
+                self.name
+            ));
+        }
+        if self.was_correct == Some(true) && stats.correct == Some(false) {
+            return Some(format!(
+                "the answer is no longer correct (compared to {})",
+                self.name
+            ));
+        }
+        let (time, previous) = stats.time.zip(self.previous_time)?;
+        let slowdown = time.mean.as_secs_f64() / previous.mean.as_secs_f64() - 1.;
+        (slowdown > max_slowdown && time.differs_from(&previous)).then(|| {
+            format!(
+                "{:.1}% slower than {} ({} -> {})",
+                slowdown * 100.,
+                self.name,
+                humantime::format_duration(previous.mean),
+                humantime::format_duration(time.mean)
+            )
+        })
+    }
+}
+impl Add for Comparison {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        debug_assert_eq!(self.name, rhs.name);
+        Self {
+            name: self.name,
+            was_correct: self.was_correct.zip(rhs.was_correct).map(|(s, r)| s && r),
             previous_time: self
                 .previous_time
-                .map(|s| rhs.previous_time.map(|r| s + r))
-                .flatten(),
+                .zip(rhs.previous_time)
+                .map(|(s, r)| s + r),
+            previous_memory: match (self.previous_memory, rhs.previous_memory) {
+                (Some(s), Some(r)) => Some(s + r),
+                (s, r) => s.or(r),
+            },
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
-struct MultilineRef(u16, u8, u8);
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct MultilineRef(u16, u8, String, u8);
 impl MultilineRef {
     fn to_html(&self, mut node: Node<'_>) -> Result<(), fmt::Error> {
         writeln!(
             node.a().attr(&format!(
-                "href='#multiline_{}_{}_{}'",
-                self.0, self.1, self.2
+                "href='#{}'",
+                multiline_id(self.0, self.1, &self.2, self.3)
             )),
             "multiline"
         )?;
@@ -536,20 +1212,150 @@ impl MultilineRef {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
-struct MultilineDest(u16, u8, u8);
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct MultilineDest(u16, u8, String, u8);
 impl MultilineDest {
     fn set_id<'n>(&self, node: Node<'n>) -> Node<'n> {
-        node.attr(&format!("id='multiline_{}_{}_{}'", self.0, self.1, self.2))
+        node.attr(&format!(
+            "id='{}'",
+            multiline_id(self.0, self.1, &self.2, self.3)
+        ))
     }
 }
 
-fn multiline_pairs(year: u16, day: u8, part: u8) -> (MultilineRef, MultilineDest) {
+/// Id of the html element with a multiline answer
+fn multiline_id(year: u16, day: u8, input: &str, part: u8) -> String {
+    if input == DEFAULT_INPUT {
+        format!("multiline_{year}_{day}_{part}")
+    } else {
+        // input names are file names, so they can contain anything
+        let input: String = input
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        format!("multiline_{year}_{day}_{part}_{input}")
+    }
+}
+
+/// Draw the values with block characters, scaled between their minimum and maximum
+fn sparkline(values: &[Duration]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().min().copied().unwrap_or_default();
+    let max = values.iter().max().copied().unwrap_or_default();
+    values
+        .iter()
+        .map(|v| {
+            if max == min {
+                BARS[BARS.len() / 2]
+            } else {
+                BARS[((*v - min).as_secs_f64() / (max - min).as_secs_f64()
+                    * (BARS.len() - 1) as f64)
+                    .round() as usize]
+            }
+        })
+        .collect()
+}
+
+fn multiline_pairs(year: u16, day: u8, input: &str, part: u8) -> (MultilineRef, MultilineDest) {
     assert!(year >= 2015);
-    assert!(day >= 1 && day <= 25);
+    assert!((1..=25).contains(&day));
     assert!(part == 1 || part == 2);
     (
-        MultilineRef(year, day, part),
-        MultilineDest(year, day, part),
+        MultilineRef(year, day, input.to_owned(), part),
+        MultilineDest(year, day, input.to_owned(), part),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Report of 2022::6 with the given results, against a baseline where both parts are correct
+    fn report(parts: [Option<Result<Measurements, FailedMeasurements>>; 2]) -> Report {
+        let answers: Answers = serde_json::from_str(r#"{"2022": {"6": ["7", "19"]}}"#).unwrap();
+        let baseline: Baselines =
+            serde_json::from_str(r#"{"2022": {"6": [{"answer": "7"}, {"answer": "19"}]}}"#)
+                .unwrap();
+        let input = InputMeasures { parse: None, parts };
+        let measures = Measures::from([(
+            2022,
+            BTreeMap::from([(6, Ok(BTreeMap::from([(DEFAULT_INPUT.to_owned(), input)])))]),
+        )]);
+        Report::new(
+            Rc::new(Filters::default()),
+            measures,
+            ExampleMeasures::new(),
+            answers,
+            vec![("base".to_owned(), baseline)],
+            &Trends::new(),
+        )
+    }
+
+    fn answer(answer: &str) -> Option<Result<Measurements, FailedMeasurements>> {
+        Some(Ok(Measurements {
+            answer: answer.to_owned(),
+            multiline: false,
+            time: None,
+            memory: None,
+        }))
+    }
+
+    /// Correctedness of the totals of the input, day, year and report
+    fn totals_correct(report: &Report) -> [Option<bool>; 4] {
+        let year = &report.years[&2022];
+        let day = year.days[&6].as_ref().unwrap();
+        [
+            day.inputs[DEFAULT_INPUT].totals.as_ref().unwrap().correct,
+            day.totals.as_ref().unwrap().correct,
+            year.totals.as_ref().unwrap().correct,
+            report.totals.as_ref().unwrap().correct,
+        ]
+    }
+
+    #[test]
+    fn failures_make_totals_wrong() {
+        assert_eq!(
+            totals_correct(&report([answer("7"), answer("19")])),
+            [Some(true); 4]
+        );
+        let panic = FailedMeasurements::Panic {
+            err: None,
+            location: None,
+            backtrace: None,
+        };
+        let report = report([answer("7"), Some(Err(panic))]);
+        assert_eq!(totals_correct(&report), [Some(false); 4]);
+        let day = report.years[&2022].days[&6].as_ref().unwrap();
+        assert_eq!(day.parts[0].as_ref().unwrap().correct, Some(true));
+        assert_eq!(day.parts[1].as_ref().unwrap().correct, Some(false));
+    }
+
+    #[test]
+    fn failures_are_regressions() {
+        assert!(report([answer("7"), answer("19")])
+            .regressions(0.1)
+            .is_empty());
+        assert_eq!(
+            report([answer("7"), answer("20")]).regressions(0.1),
+            ["2022 day 6 part 2: the answer is no longer correct (compared to base)"]
+        );
+
+        let timeout = FailedMeasurements::Timeout(Duration::from_nanos(1));
+        assert_eq!(
+            report([Some(Err(timeout.clone())), Some(Err(timeout))]).regressions(0.1),
+            [
+                "2022 day 6 part 1: Timed out after 1ns (was correct in base)",
+                "2022 day 6 part 2: Timed out after 1ns (was correct in base)"
+            ]
+        );
+        let panic = FailedMeasurements::Panic {
+            err: Some("oops".into()),
+            location: None,
+            backtrace: None,
+        };
+        assert_eq!(
+            report([answer("7"), Some(Err(panic))]).regressions(0.1),
+            ["2022 day 6 part 2: Panicked: oops (was correct in base)"]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Conversion of the report into the JSON schema
+
+use either::Either::{Left, Right};
+
+use crate::{bench, memory::MemoryStats, schema, FailedMeasurements, InputError};
+
+use super::{Day, DayInput, Example, Failure, Part, Report, Stats, Year};
+
+impl Report {
+    pub(crate) fn to_schema(&self) -> schema::Report {
+        schema::Report {
+            schema_version: schema::SCHEMA_VERSION,
+            generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            filters: self.problems.to_string(),
+            examples: self.examples.iter().map(Example::to_schema).collect(),
+            totals: self.totals.as_ref().map(Stats::to_schema),
+            years: self
+                .years
+                .iter()
+                .map(|(y, year)| (*y, year.to_schema()))
+                .collect(),
+        }
+    }
+}
+
+impl Example {
+    fn to_schema(&self) -> schema::Example {
+        schema::Example {
+            year: self.year,
+            day: self.day,
+            name: self.name.clone(),
+            parts: self.parts.each_ref().map(|p| {
+                p.as_ref().map(|p| {
+                    let params = p
+                        .params
+                        .iter()
+                        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                        .collect();
+                    match &p.answer {
+                        Ok(answer) => schema::ExamplePart::Answered {
+                            answer: answer.clone(),
+                            expected: p.expected.clone(),
+                            correct: p.passed(),
+                            params,
+                        },
+                        Err(err) => schema::ExamplePart::Failed {
+                            expected: p.expected.clone(),
+                            kind: err.schema_kind(),
+                            message: err.to_string(),
+                            backtrace: err.backtrace().map(str::to_owned),
+                            params,
+                        },
+                    }
+                })
+            }),
+        }
+    }
+}
+
+impl Year {
+    fn to_schema(&self) -> schema::Year {
+        schema::Year {
+            totals: self.totals.as_ref().map(Stats::to_schema),
+            days: self
+                .days
+                .iter()
+                .map(|(d, day)| {
+                    (
+                        *d,
+                        match day {
+                            Ok(day) => day.to_schema(self),
+                            Err(err) => match &*err.err {
+                                InputError::Missing => schema::Day::MissingInput,
+                                InputError::Empty(path) => schema::Day::EmptyInput {
+                                    path: path.display().to_string(),
+                                },
+                                err => schema::Day::InputError {
+                                    error: err.to_string(),
+                                },
+                            },
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Day {
+    fn to_schema(&self, year: &Year) -> schema::Day {
+        schema::Day::Run(Box::new(schema::DayRun {
+            inputs: self
+                .inputs
+                .iter()
+                .map(|(name, input)| (name.clone(), input.to_schema(year)))
+                .collect(),
+            parse: self.parse.as_ref().map(Stats::to_schema),
+            parts: self
+                .parts
+                .each_ref()
+                .map(|p| p.as_ref().map(Stats::to_schema)),
+            totals: self.totals.as_ref().map(Stats::to_schema),
+        }))
+    }
+}
+
+impl DayInput {
+    fn to_schema(&self, year: &Year) -> schema::DayInput {
+        schema::DayInput {
+            parse: self.parse.as_ref().map(|p| match p {
+                Ok(p) => schema::Parse::Parsed {
+                    stats: p.stats.to_schema(),
+                },
+                Err(Failure { err, .. }) => schema::Parse::Failed {
+                    kind: err.schema_kind(),
+                    message: err.to_string(),
+                    backtrace: err.backtrace().map(str::to_owned),
+                },
+            }),
+            parts: self.parts.each_ref().map(|p| {
+                p.as_ref().map(|p| match p {
+                    Ok(p) => p.to_schema(year),
+                    Err(Failure { err, .. }) => err.to_schema(),
+                })
+            }),
+            totals: self.totals.as_ref().map(Stats::to_schema),
+        }
+    }
+}
+
+impl Part {
+    fn to_schema(&self, year: &Year) -> schema::Part {
+        let (answer, multiline) = match &self.answer {
+            Left(answer) => (answer.clone(), false),
+            Right(mref) => (year.multiline(mref).to_owned(), true),
+        };
+        schema::Part::Answered {
+            answer,
+            multiline,
+            expected: self.expected.clone(),
+            rejection: self.rejection.clone(),
+            stats: self.stats.to_schema(),
+        }
+    }
+}
+
+impl FailedMeasurements {
+    fn to_schema(&self) -> schema::Part {
+        schema::Part::Failed {
+            kind: self.schema_kind(),
+            message: self.to_string(),
+            backtrace: self.backtrace().map(str::to_owned),
+        }
+    }
+
+    fn schema_kind(&self) -> schema::FailureKind {
+        match self {
+            FailedMeasurements::Panic { .. } => schema::FailureKind::Panic,
+            FailedMeasurements::Error { .. } => schema::FailureKind::Error,
+            FailedMeasurements::Timeout(_) => schema::FailureKind::Timeout,
+            FailedMeasurements::StackOverflow => schema::FailureKind::StackOverflow,
+            FailedMeasurements::OutOfMemory => schema::FailureKind::OutOfMemory,
+            FailedMeasurements::Aborted => schema::FailureKind::Aborted,
+            FailedMeasurements::Signal(_) => schema::FailureKind::Signal,
+            FailedMeasurements::Exited(_) => schema::FailureKind::Exited,
+            FailedMeasurements::Spawn(_) => schema::FailureKind::Spawn,
+        }
+    }
+}
+
+impl Stats {
+    fn to_schema(&self) -> schema::Stats {
+        schema::Stats {
+            correct: self.correct,
+            time: self.time.map(bench::TimeStats::to_schema),
+            memory: self.memory.map(MemoryStats::to_schema),
+            baselines: self
+                .baselines
+                .iter()
+                .map(|c| schema::BaselineComparison {
+                    name: c.name.to_string(),
+                    was_correct: c.was_correct,
+                    time: self.time.zip(c.previous_time).map(|(time, previous)| {
+                        schema::BaselineDelta {
+                            previous: previous.to_schema(),
+                            delta_ns: time.mean.as_nanos() as i64 - previous.mean.as_nanos() as i64,
+                            significant: time.differs_from(&previous),
+                        }
+                    }),
+                    memory: self
+                        .memory
+                        .zip(c.previous_memory)
+                        .map(|(memory, previous)| schema::MemoryDelta {
+                            previous: previous.to_schema(),
+                            delta_peak_bytes: memory.peak as i64 - previous.peak as i64,
+                            delta_total_bytes: memory.total as i64 - previous.total as i64,
+                            delta_allocations: memory.allocations as i64
+                                - previous.allocations as i64,
+                        }),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl bench::TimeStats {
+    fn to_schema(self) -> schema::TimeStats {
+        schema::TimeStats {
+            samples: self.samples,
+            min_ns: self.min.as_nanos() as u64,
+            median_ns: self.median.as_nanos() as u64,
+            mean_ns: self.mean.as_nanos() as u64,
+            stddev_ns: self.stddev.as_nanos() as u64,
+            p95_ns: self.p95.as_nanos() as u64,
+        }
+    }
+}
+
+impl MemoryStats {
+    fn to_schema(self) -> schema::MemoryStats {
+        schema::MemoryStats {
+            peak_bytes: self.peak,
+            total_bytes: self.total,
+            allocations: self.allocations,
+        }
+    }
+}
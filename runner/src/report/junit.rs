@@ -0,0 +1,264 @@
+//! Rendering of the report as JUnit XML, for CI systems
+//!
+//! Each year is a testsuite, and each part a testcase, as is each parsing step. The examples are in
+//! their own testsuite.
+
+use std::fmt::{self, Write};
+
+use either::Either::{Left, Right};
+
+use crate::FailedMeasurements;
+
+use super::{Day, Failure, Report, Year};
+
+/// A testcase, before being written
+struct TestCase {
+    classname: String,
+    name: String,
+    time: f64,
+    outcome: Outcome,
+}
+
+enum Outcome {
+    Passed,
+    Skipped(String),
+    Failure { message: String, body: String },
+    Error { kind: &'static str, message: String },
+}
+
+impl Report {
+    pub(crate) fn render_junit(&self, out: &mut impl Write) -> fmt::Result {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<testsuites name="{}">"#,
+            Escaped(&format!("Advent of Code {}", self.problems))
+        )?;
+        if !self.examples.is_empty() {
+            render_suite(out, "examples", self.examples_junit_cases())?;
+        }
+        for year in self.years.values() {
+            year.render_junit(out)?;
+        }
+        writeln!(out, "</testsuites>")
+    }
+
+    fn examples_junit_cases(&self) -> Vec<TestCase> {
+        self.examples
+            .iter()
+            .flat_map(|example| {
+                example.parts.iter().zip(1..).filter_map(|(part, p)| {
+                    let part = part.as_ref()?;
+                    let outcome = match &part.answer {
+                        _ if part.passed() => Outcome::Passed,
+                        Ok(answer) => Outcome::Failure {
+                            message: "Wrong answer".to_owned(),
+                            body: format!("expected:\n{}\ngot:\n{answer}", part.expected),
+                        },
+                        Err(err) => Outcome::Error {
+                            kind: err.class(),
+                            message: error_message(err),
+                        },
+                    };
+                    Some(TestCase {
+                        classname: format!("{}.examples", classname(example.year, example.day)),
+                        name: format!("example {} part {p}", example.name),
+                        time: 0.,
+                        outcome,
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+impl Year {
+    fn render_junit(&self, out: &mut impl Write) -> fmt::Result {
+        let cases: Vec<_> = self
+            .days
+            .iter()
+            .flat_map(|(d, day)| match day {
+                Ok(day) => day.junit_cases(self),
+                Err(err) => vec![TestCase {
+                    classname: classname(self.year, *d),
+                    name: "input".to_owned(),
+                    time: 0.,
+                    outcome: if err.is_skipped() {
+                        Outcome::Skipped(err.skipped_label().to_owned())
+                    } else {
+                        Outcome::Error {
+                            kind: "input",
+                            message: format!("Error in reading input: {}", err.err),
+                        }
+                    },
+                }],
+            })
+            .collect();
+        render_suite(out, &self.year.to_string(), cases)
+    }
+}
+
+fn render_suite(out: &mut impl Write, name: &str, cases: Vec<TestCase>) -> fmt::Result {
+    let count = |f: fn(&Outcome) -> bool| cases.iter().filter(|c| f(&c.outcome)).count();
+    writeln!(
+        out,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.9}">"#,
+        Escaped(name),
+        cases.len(),
+        count(|o| matches!(o, Outcome::Failure { .. })),
+        count(|o| matches!(o, Outcome::Error { .. })),
+        count(|o| matches!(o, Outcome::Skipped(_))),
+        cases.iter().map(|c| c.time).sum::<f64>()
+    )?;
+    for case in cases {
+        write!(
+            out,
+            r#"    <testcase classname="{}" name="{}" time="{:.9}""#,
+            Escaped(&case.classname),
+            Escaped(&case.name),
+            case.time
+        )?;
+        match case.outcome {
+            Outcome::Passed => writeln!(out, "/>")?,
+            Outcome::Skipped(message) => {
+                writeln!(out, ">")?;
+                writeln!(out, r#"      <skipped message="{}"/>"#, Escaped(&message))?;
+                writeln!(out, "    </testcase>")?;
+            }
+            Outcome::Failure { message, body } => {
+                writeln!(out, ">")?;
+                writeln!(
+                    out,
+                    r#"      <failure type="wrong-answer" message="{}">"#,
+                    Escaped(&message),
+                )?;
+                writeln!(
+                    out,
+                    "<![CDATA[{}]]></failure>",
+                    body.replace("]]>", "]]]]><![CDATA[>")
+                )?;
+                writeln!(out, "    </testcase>")?;
+            }
+            Outcome::Error { kind, message } => {
+                writeln!(out, ">")?;
+                writeln!(
+                    out,
+                    r#"      <error type="{kind}" message="{}">"#,
+                    Escaped(message.lines().next().unwrap_or_default()),
+                )?;
+                writeln!(
+                    out,
+                    "<![CDATA[{}]]></error>",
+                    message.replace("]]>", "]]]]><![CDATA[>")
+                )?;
+                writeln!(out, "    </testcase>")?;
+            }
+        }
+    }
+    writeln!(out, "  </testsuite>")
+}
+
+impl Day {
+    fn junit_cases(&self, year: &Year) -> Vec<TestCase> {
+        let named_inputs = self.has_named_inputs();
+        let on_input = |name: &str| {
+            if named_inputs {
+                format!(" on input {name}")
+            } else {
+                String::new()
+            }
+        };
+        let parse_cases = self.inputs.iter().filter_map(|(name, input)| {
+            let (outcome, time) = match input.parse.as_ref()? {
+                Ok(parse) => (
+                    Outcome::Passed,
+                    parse.stats.time.map(|t| t.mean.as_secs_f64()),
+                ),
+                Err(Failure { err, .. }) => (
+                    Outcome::Error {
+                        kind: err.class(),
+                        message: error_message(err),
+                    },
+                    None,
+                ),
+            };
+            Some(TestCase {
+                classname: classname(year.year, self.day),
+                name: format!("parse{}", on_input(name)),
+                time: time.unwrap_or_default(),
+                outcome,
+            })
+        });
+        let part_cases = self
+            .inputs
+            .iter()
+            .flat_map(|(name, input)| input.parts.iter().zip(1..).map(move |p| (name, p)))
+            .filter_map(|(name, (part, p))| {
+                let (outcome, time) = match part.as_ref()? {
+                    Ok(part) => {
+                        let answer = match &part.answer {
+                            Left(answer) => answer,
+                            Right(mref) => year.multiline(mref),
+                        };
+                        let outcome = match (&part.expected, part.stats.correct) {
+                            (_, Some(true)) => Outcome::Passed,
+                            (Some(expected), Some(false)) => Outcome::Failure {
+                                message: "Wrong answer".to_owned(),
+                                body: format!("expected:\n{expected}\ngot:\n{answer}"),
+                            },
+                            _ => Outcome::Skipped(format!("Unknown correct answer, got {answer}")),
+                        };
+                        let time = part.stats.time.map(|t| t.mean.as_secs_f64());
+                        (outcome, time.unwrap_or_default())
+                    }
+                    Err(Failure { err, .. }) => (
+                        Outcome::Error {
+                            kind: err.class(),
+                            message: error_message(err),
+                        },
+                        0.,
+                    ),
+                };
+                Some(TestCase {
+                    classname: classname(year.year, self.day),
+                    name: format!("part {p}{}", on_input(name)),
+                    time,
+                    outcome,
+                })
+            });
+        parse_cases.chain(part_cases).collect()
+    }
+}
+
+fn classname(year: u16, day: u8) -> String {
+    format!("aoc.{year}.day{day:02}")
+}
+
+/// Description of a failure, followed by the backtrace of a panic
+fn error_message(err: &FailedMeasurements) -> String {
+    match err.backtrace() {
+        Some(backtrace) => format!("{err}\n\nBacktrace:\n{backtrace}"),
+        None => err.to_string(),
+    }
+}
+
+/// Text escaped for XML attributes and content
+///
+/// Newlines are escaped too, so they survive in attributes.
+struct Escaped<'a>(&'a str);
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                '\n' => f.write_str("&#10;")?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,476 @@
+//! Rendering of the report as colored tables for the terminal
+
+use std::{
+    fmt::{self, Write},
+    time::Duration,
+};
+
+use either::Either::{Left, Right};
+
+use crate::{
+    bench::TimeStats,
+    memory::{Bytes, MemoryStats},
+    FailedMeasurements, DEFAULT_INPUT,
+};
+
+use super::{Day, DayInput, Failure, Report, Stats, Year};
+
+pub(super) const CHECK: &str = "✔";
+pub(super) const CROSS: &str = "✘";
+
+/// Styles used by the terminal report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Style {
+    Plain,
+    Bold,
+    Dim,
+    Green,
+    Red,
+    Yellow,
+    Magenta,
+}
+impl Style {
+    fn ansi(&self) -> &'static str {
+        match self {
+            Style::Plain => "",
+            Style::Bold => "\x1b[1m",
+            Style::Dim => "\x1b[2m",
+            Style::Green => "\x1b[32m",
+            Style::Red => "\x1b[31m",
+            Style::Yellow => "\x1b[33m",
+            Style::Magenta => "\x1b[35m",
+        }
+    }
+}
+
+/// A piece of text in a cell
+#[derive(Debug, Clone)]
+pub(super) struct Span(pub(super) String, pub(super) Style);
+
+/// A table cell, made of multiple spans
+#[derive(Debug, Clone, Default)]
+pub(super) struct Cell(pub(super) Vec<Span>);
+impl Cell {
+    pub(super) fn new(text: impl Into<String>, style: Style) -> Self {
+        Self(vec![Span(text.into(), style)])
+    }
+
+    pub(super) fn push(mut self, text: impl Into<String>, style: Style) -> Self {
+        self.0.push(Span(text.into(), style));
+        self
+    }
+
+    fn width(&self) -> usize {
+        self.0.iter().map(|Span(t, _)| t.chars().count()).sum()
+    }
+
+    fn render(&self, out: &mut impl Write, width: usize, color: bool) -> fmt::Result {
+        for Span(text, style) in &self.0 {
+            if color && *style != Style::Plain {
+                write!(out, "{}{text}\x1b[0m", style.ansi())?;
+            } else {
+                write!(out, "{text}")?;
+            }
+        }
+        write!(out, "{:1$}", "", width.saturating_sub(self.width()))
+    }
+}
+
+/// A row of the table, with optional lines to print below it
+#[derive(Debug, Clone)]
+struct Row {
+    cells: Vec<Cell>,
+    below: Vec<(String, String)>,
+}
+
+impl Report {
+    pub(crate) fn render_term(&self, out: &mut impl Write, color: bool) -> fmt::Result {
+        let title = Cell::new("Report for the query ", Style::Bold)
+            .push(format!("\"{}\"", self.problems), Style::Bold);
+        title.render(out, 0, color)?;
+        writeln!(out)?;
+
+        if !self.examples.is_empty() {
+            writeln!(out)?;
+            self.render_term_examples(out, color)?;
+        }
+
+        for year in self.years.values() {
+            writeln!(out)?;
+            year.render_term(out, color)?;
+        }
+
+        if let Some(totals) = &self.totals {
+            writeln!(out)?;
+            let mut cells = vec![Cell::new("Combined totals:", Style::Bold)];
+            cells.extend(totals.term_cells());
+            for cell in cells {
+                cell.render(out, 0, color)?;
+                write!(out, " ")?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+impl Report {
+    /// A summary of the examples, with the details of the failed ones
+    fn render_term_examples(&self, out: &mut impl Write, color: bool) -> fmt::Result {
+        let (passed, total) = self.examples_summary();
+        let style = if passed == total {
+            Style::Green
+        } else {
+            Style::Red
+        };
+        Cell::new("Examples: ", Style::Bold)
+            .push(format!("{passed}/{total} passed"), style)
+            .render(out, 0, color)?;
+        writeln!(out)?;
+        for example in &self.examples {
+            for (part, p) in example.parts.iter().zip(1..) {
+                let Some(part) = part.as_ref().filter(|p| !p.passed()) else {
+                    continue;
+                };
+                let params = if part.params.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", part.params)
+                };
+                Cell::new(
+                    format!(
+                        "{CROSS} {} day {} example {} part {p}{params}:",
+                        example.year, example.day, example.name
+                    ),
+                    Style::Red,
+                )
+                .render(out, 0, color)?;
+                match &part.answer {
+                    Ok(answer) if !answer.contains('\n') && !part.expected.contains('\n') => {
+                        writeln!(out, " expected {}, got {answer}", part.expected)?
+                    }
+                    Ok(answer) => {
+                        writeln!(out)?;
+                        for (title, content) in [("expected", &part.expected), ("got", answer)] {
+                            Cell::new(format!("    {title}:"), Style::Dim).render(out, 0, color)?;
+                            writeln!(out)?;
+                            for line in content.lines() {
+                                writeln!(out, "    {line}")?;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        write!(out, " ")?;
+                        err.term_cell().render(out, 0, color)?;
+                        writeln!(out)?;
+                        for line in err.to_string().lines().skip(1) {
+                            writeln!(out, "    {line}")?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Year {
+    fn render_term(&self, out: &mut impl Write, color: bool) -> fmt::Result {
+        Cell::new(format!("Year {}", self.year), Style::Bold).render(out, 0, color)?;
+        writeln!(out)?;
+
+        let parse = self.has_parse();
+        let header = Row {
+            cells: ["Day"]
+                .into_iter()
+                .chain(parse.then_some("Parse"))
+                .chain([
+                    "Part 1", "Time", "Ok", "Part 2", "Time", "Ok", "Total", "Ok",
+                ])
+                .map(|h| Cell::new(h, Style::Bold))
+                .collect(),
+            below: vec![],
+        };
+        let columns = header.cells.len();
+        let mut rows = vec![header];
+        for (d, day) in &self.days {
+            match day {
+                Ok(day) => rows.extend(day.term_rows(self, parse)),
+                Err(err) if err.is_skipped() => rows.push(Row {
+                    cells: vec![
+                        Cell::new(d.to_string(), Style::Bold),
+                        Cell::new(err.skipped_label(), Style::Dim),
+                    ],
+                    below: vec![],
+                }),
+                Err(err) => rows.push(Row {
+                    cells: vec![
+                        Cell::new(d.to_string(), Style::Bold),
+                        Cell::new(
+                            format!("{CROSS} Error in reading input: {}", err.err),
+                            Style::Red,
+                        ),
+                    ],
+                    below: vec![],
+                }),
+            }
+        }
+        let mut totals = vec![Cell::new("Totals", Style::Bold)];
+        totals.extend((0..columns - 3).map(|_| Cell::default()));
+        totals.extend(totals_term_cells(self.totals.as_ref()));
+        rows.push(Row {
+            cells: totals,
+            below: vec![],
+        });
+
+        // the last cell of a row can be wider than its column, as nothing follows it
+        let mut widths = vec![0; columns];
+        for row in &rows {
+            let last = row.cells.len() - 1;
+            for (i, cell) in row.cells.iter().enumerate().take(last) {
+                widths[i] = widths[i].max(cell.width());
+            }
+        }
+
+        for Row { cells, below } in rows {
+            for (i, cell) in cells.iter().enumerate() {
+                if i > 0 {
+                    write!(out, " │ ")?;
+                }
+                cell.render(out, if i + 1 < cells.len() { widths[i] } else { 0 }, color)?;
+            }
+            writeln!(out)?;
+            for (title, content) in below {
+                Cell::new(format!("    {title}:"), Style::Dim).render(out, 0, color)?;
+                writeln!(out)?;
+                for line in content.lines() {
+                    writeln!(out, "    {line}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Day {
+    /// A row for the day, followed by a row for each input if it has named ones
+    fn term_rows(&self, year: &Year, parse: bool) -> Vec<Row> {
+        let day = Cell::new(self.day.to_string(), Style::Bold);
+        if !self.has_named_inputs() {
+            return self
+                .inputs
+                .values()
+                .map(|input| input.term_row(day.clone(), year, parse))
+                .collect();
+        }
+
+        let mut cells = vec![day];
+        if parse {
+            cells.push(match &self.parse {
+                Some(stats) => {
+                    let [time, _] = stats.term_cells();
+                    time
+                }
+                None => Cell::new("-", Style::Dim),
+            });
+        }
+        for stats in &self.parts {
+            match stats {
+                Some(stats) => {
+                    cells.push(Cell::new(
+                        format!("{} inputs", self.inputs.len()),
+                        correctedness_style(stats.correct),
+                    ));
+                    cells.extend(stats.term_cells());
+                }
+                None => cells.extend((0..3).map(|_| Cell::new("-", Style::Dim))),
+            }
+        }
+        cells.extend(totals_term_cells(self.totals.as_ref()));
+        let mut rows = vec![Row {
+            cells,
+            below: vec![],
+        }];
+        rows.extend(self.inputs.iter().map(|(name, input)| {
+            input.term_row(Cell::new(format!("  {name}"), Style::Dim), year, parse)
+        }));
+        rows
+    }
+}
+
+impl DayInput {
+    fn term_row(&self, first: Cell, year: &Year, parse: bool) -> Row {
+        let mut cells = vec![first];
+        let mut below = vec![];
+        if parse {
+            cells.push(match &self.parse {
+                Some(Ok(parse)) => {
+                    let [time, _] = parse.stats.term_cells();
+                    time
+                }
+                Some(Err(Failure { err, .. })) => {
+                    if let FailedMeasurements::Error { chain } = err {
+                        if chain.len() > 1 {
+                            below.push(("Parse error".to_owned(), err.to_string()));
+                        }
+                    }
+                    err.term_cell()
+                }
+                None => Cell::new("-", Style::Dim),
+            });
+        }
+        for (part, p) in self.parts.iter().zip(1..) {
+            match part {
+                Some(Ok(part)) => {
+                    let style = correctedness_style(part.stats.correct);
+                    let mut answer = match &part.answer {
+                        Left(answer) => Cell::new(answer.clone(), style),
+                        Right(mref) => {
+                            let title = if mref.2 == DEFAULT_INPUT {
+                                format!("Part {}", part.part)
+                            } else {
+                                format!("Part {} on input {}", part.part, mref.2)
+                            };
+                            below.push((title, year.multiline(mref).to_owned()));
+                            Cell::new("(multiline)", style)
+                        }
+                    };
+                    if let Some(rejection) = &part.rejection {
+                        answer = answer.push(format!(" ({rejection})"), Style::Dim);
+                    }
+                    cells.push(answer);
+                    cells.extend(part.stats.term_cells());
+                }
+                Some(Err(Failure { err, .. })) => {
+                    if let FailedMeasurements::Error { chain } = err {
+                        if chain.len() > 1 {
+                            below.push((format!("Part {p} error"), err.to_string()));
+                        }
+                    }
+                    cells.push(err.term_cell());
+                    cells.push(Cell::default());
+                    cells.push(Cell::new(CROSS, Style::Red));
+                }
+                None => cells.extend((0..3).map(|_| Cell::new("-", Style::Dim))),
+            }
+        }
+        cells.extend(totals_term_cells(self.totals.as_ref()));
+        Row { cells, below }
+    }
+}
+
+fn totals_term_cells(totals: Option<&Stats>) -> [Cell; 2] {
+    match totals {
+        Some(t) => t.term_cells(),
+        None => [Cell::new("-", Style::Dim), Cell::new("-", Style::Dim)],
+    }
+}
+
+impl FailedMeasurements {
+    pub(super) fn term_cell(&self) -> Cell {
+        let style = match self {
+            FailedMeasurements::Timeout(_) => Style::Yellow,
+            FailedMeasurements::Error { .. } => Style::Magenta,
+            _ => Style::Red,
+        };
+        Cell::new(self.to_string().lines().next().unwrap_or_default(), style)
+    }
+}
+
+impl Stats {
+    /// Time and correctedness cells, the time one also showing the peak heap usage if measured
+    pub(super) fn term_cells(&self) -> [Cell; 2] {
+        let mut time = match &self.time {
+            Some(time) => {
+                let mut cell = Cell::new(short_duration(time.mean), Style::Plain);
+                let labelled = self.baselines.len() > 1;
+                for comparison in &self.baselines {
+                    if let Some(previous) = &comparison.previous_time {
+                        cell = cell.push(" ", Style::Plain);
+                        if labelled {
+                            cell = cell.push(format!("{} ", comparison.name), Style::Dim);
+                        }
+                        cell = time_diff(cell, time, previous);
+                    }
+                }
+                cell
+            }
+            None => Cell::new("-", Style::Dim),
+        };
+        if let Some(memory) = &self.memory {
+            time = time.push(format!(" {}", Bytes(memory.peak)), Style::Plain);
+            let labelled = self.baselines.len() > 1;
+            for comparison in &self.baselines {
+                if let Some(previous) = &comparison.previous_memory {
+                    time = time.push(" ", Style::Plain);
+                    if labelled {
+                        time = time.push(format!("{} ", comparison.name), Style::Dim);
+                    }
+                    time = memory_diff(time, memory, previous);
+                }
+            }
+        }
+        let check = match self.correct {
+            Some(correct) => {
+                let mut cell = if correct {
+                    Cell::new(CHECK, Style::Green)
+                } else {
+                    Cell::new(CROSS, Style::Red)
+                };
+                for was_correct in self.baselines.iter().filter_map(|c| c.was_correct) {
+                    if was_correct != correct {
+                        cell = cell.push(
+                            format!("({})", if was_correct { CHECK } else { CROSS }),
+                            Style::Dim,
+                        );
+                    }
+                }
+                cell
+            }
+            None => Cell::new("-", Style::Dim),
+        };
+        [time, check]
+    }
+}
+
+fn time_diff(cell: Cell, time: &TimeStats, previous: &TimeStats) -> Cell {
+    let (sign, diff, style) = if previous.mean <= time.mean {
+        ("+", time.mean - previous.mean, Style::Red)
+    } else {
+        ("-", previous.mean - time.mean, Style::Green)
+    };
+    let style = if time.differs_from(previous) {
+        style
+    } else {
+        Style::Dim
+    };
+    cell.push(format!("[{sign}{}]", short_duration(diff)), style)
+}
+
+fn memory_diff(cell: Cell, memory: &MemoryStats, previous: &MemoryStats) -> Cell {
+    let (sign, diff, style) = if previous.peak <= memory.peak {
+        ("+", memory.peak - previous.peak, Style::Red)
+    } else {
+        ("-", previous.peak - memory.peak, Style::Green)
+    };
+    let style = if diff == 0 { Style::Dim } else { style };
+    cell.push(format!("[{sign}{}]", Bytes(diff)), style)
+}
+
+pub(super) fn correctedness_style(correct: Option<bool>) -> Style {
+    match correct {
+        Some(true) => Style::Green,
+        Some(false) => Style::Red,
+        None => Style::Plain,
+    }
+}
+
+/// Format a duration keeping only the two most significant units
+pub(super) fn short_duration(d: Duration) -> String {
+    humantime::format_duration(d)
+        .to_string()
+        .split(' ')
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
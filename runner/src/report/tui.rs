@@ -0,0 +1,316 @@
+//! Rows of the interactive terminal UI, with the details shown for each of them
+
+use either::Either::{Left, Right};
+use ratatui::{
+    style::{Color, Modifier, Style as TuiStyle},
+    text::{Line, Span as TuiSpan},
+};
+
+use crate::{memory::Bytes, FailedMeasurements, Phase, DEFAULT_INPUT};
+
+use super::{
+    term::{correctedness_style, short_duration, Cell, Span, Style, CHECK, CROSS},
+    Failure, Report, Stats, Year,
+};
+
+/// What a row of the UI shows, in the order of the rows
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum RowKey {
+    Year(u16),
+    Day(u16, u8),
+    /// A phase of a day on an input
+    Step(u16, u8, String, Phase),
+}
+impl RowKey {
+    /// The row this one is nested in
+    pub(crate) fn parent(&self) -> Option<RowKey> {
+        match self {
+            RowKey::Year(_) => None,
+            RowKey::Day(y, _) => Some(RowKey::Year(*y)),
+            RowKey::Step(y, d, _, _) => Some(RowKey::Day(*y, *d)),
+        }
+    }
+}
+
+/// A row of the UI, as the name, answer, time and correctedness columns
+#[derive(Debug, Clone)]
+pub(crate) struct TuiRow {
+    pub(crate) key: RowKey,
+    pub(crate) cells: [Line<'static>; 4],
+    /// Shown in the side pane when the row is selected
+    pub(crate) details: Vec<Line<'static>>,
+}
+
+impl Report {
+    /// The rows of the years, days and steps of the report
+    pub(crate) fn tui_rows(&self) -> Vec<TuiRow> {
+        let mut rows = vec![];
+        for (&y, year) in &self.years {
+            rows.push(TuiRow {
+                key: RowKey::Year(y),
+                cells: totals_cells(Cell::new(format!("Year {y}"), Style::Bold), &year.totals),
+                details: totals_details(format!("Year {y}"), &year.totals),
+            });
+            for (&d, day) in &year.days {
+                let name = Cell::new(format!("  Day {d}"), Style::Bold);
+                let day = match day {
+                    Ok(day) => day,
+                    Err(err) if err.is_skipped() => {
+                        rows.push(TuiRow {
+                            key: RowKey::Day(y, d),
+                            cells: [
+                                line(name),
+                                line(Cell::new(err.skipped_label(), Style::Dim)),
+                                Line::default(),
+                                line(Cell::new("-", Style::Dim)),
+                            ],
+                            details: vec![
+                                title(format!("{y}::{d}")),
+                                Line::from(err.err.to_string()),
+                            ],
+                        });
+                        continue;
+                    }
+                    Err(err) => {
+                        let error = format!("{CROSS} Error in reading input: {}", err.err);
+                        rows.push(TuiRow {
+                            key: RowKey::Day(y, d),
+                            cells: [
+                                line(name),
+                                line(Cell::new(error.clone(), Style::Red)),
+                                Line::default(),
+                                line(Cell::new(CROSS, Style::Red)),
+                            ],
+                            details: vec![title(format!("{y}::{d}")), Line::from(error)],
+                        });
+                        continue;
+                    }
+                };
+                rows.push(TuiRow {
+                    key: RowKey::Day(y, d),
+                    cells: totals_cells(name, &day.totals),
+                    details: totals_details(format!("{y}::{d}"), &day.totals),
+                });
+                for (input, results) in &day.inputs {
+                    let on_input = if input == DEFAULT_INPUT {
+                        String::new()
+                    } else {
+                        format!(" {input}")
+                    };
+                    if let Some(parse) = &results.parse {
+                        let key = RowKey::Step(y, d, input.clone(), Phase::Parse);
+                        let name = Cell::new(format!("    Parse{on_input}"), Style::Plain);
+                        let header = format!("{y}::{d} parsing{}", on_input_details(input));
+                        rows.push(match parse {
+                            Ok(parse) => {
+                                let [time, _] = parse.stats.term_cells();
+                                let mut details = vec![title(header)];
+                                push_stats(&mut details, &parse.stats);
+                                TuiRow {
+                                    key,
+                                    cells: [
+                                        line(name),
+                                        Line::default(),
+                                        line(time),
+                                        Line::default(),
+                                    ],
+                                    details,
+                                }
+                            }
+                            Err(Failure { err, .. }) => failed_row(key, name, header, err),
+                        });
+                    }
+                    for (part, p) in results.parts.iter().zip(1..) {
+                        let key = RowKey::Step(y, d, input.clone(), Phase::Part(p));
+                        let name = Cell::new(format!("    Part {p}{on_input}"), Style::Plain);
+                        let header = format!("{y}::{d}::{p}{}", on_input_details(input));
+                        rows.push(match part {
+                            Some(Ok(part)) => part_row(key, name, header, part, year),
+                            Some(Err(Failure { err, .. })) => failed_row(key, name, header, err),
+                            None => TuiRow {
+                                key,
+                                cells: [
+                                    line(name),
+                                    line(Cell::new("-", Style::Dim)),
+                                    line(Cell::new("-", Style::Dim)),
+                                    line(Cell::new("-", Style::Dim)),
+                                ],
+                                details: vec![title(header), Line::from("Not run")],
+                            },
+                        });
+                    }
+                }
+            }
+        }
+        rows
+    }
+}
+
+fn part_row(key: RowKey, name: Cell, header: String, part: &super::Part, year: &Year) -> TuiRow {
+    let style = correctedness_style(part.stats.correct);
+    let mut details = vec![title(header), Line::from("Answer:")];
+    let mut answer = match &part.answer {
+        Left(answer) => {
+            details.push(line(Cell::new(format!("  {answer}"), style)));
+            Cell::new(answer.clone(), style)
+        }
+        Right(mref) => {
+            details.extend(
+                (year.multiline(mref).lines()).map(|l| line(Cell::new(format!("  {l}"), style))),
+            );
+            Cell::new("(multiline)", style)
+        }
+    };
+    if let Some(expected) = &part.expected {
+        details.push(Line::from(format!("Expected: {expected}")));
+    }
+    if let Some(rejection) = &part.rejection {
+        answer = answer.push(format!(" ({rejection})"), Style::Dim);
+        details.push(Line::from(format!("Wrong: {rejection}")));
+    }
+    push_stats(&mut details, &part.stats);
+    let [time, check] = part.stats.term_cells();
+    TuiRow {
+        key,
+        cells: [line(name), line(answer), line(time), line(check)],
+        details,
+    }
+}
+
+fn failed_row(key: RowKey, name: Cell, header: String, err: &FailedMeasurements) -> TuiRow {
+    let mut details = vec![title(header), line(Cell::new("Failed:", Style::Red))];
+    details.extend(
+        err.to_string()
+            .lines()
+            .map(|l| Line::from(format!("  {l}"))),
+    );
+    if let Some(backtrace) = err.backtrace() {
+        details.push(Line::from("Backtrace:"));
+        details.extend(
+            backtrace
+                .lines()
+                .map(|l| line(Cell::new(l.to_owned(), Style::Dim))),
+        );
+    }
+    TuiRow {
+        key,
+        cells: [
+            line(name),
+            line(err.term_cell()),
+            Line::default(),
+            line(Cell::new(CROSS, Style::Red)),
+        ],
+        details,
+    }
+}
+
+fn totals_cells(name: Cell, totals: &Option<Stats>) -> [Line<'static>; 4] {
+    match totals {
+        Some(totals) => {
+            let [time, check] = totals.term_cells();
+            [line(name), Line::default(), line(time), line(check)]
+        }
+        None => [
+            line(name),
+            Line::default(),
+            line(Cell::new("-", Style::Dim)),
+            line(Cell::new("-", Style::Dim)),
+        ],
+    }
+}
+
+fn totals_details(header: String, totals: &Option<Stats>) -> Vec<Line<'static>> {
+    match totals {
+        Some(totals) => {
+            let mut details = vec![title(format!("{header} totals"))];
+            push_stats(&mut details, totals);
+            details
+        }
+        None => vec![title(header), Line::from("Nothing run yet")],
+    }
+}
+
+/// Add the correctedness, running time, heap usage and comparisons with the baselines
+fn push_stats(details: &mut Vec<Line<'static>>, stats: &Stats) {
+    if let Some(correct) = stats.correct {
+        details.push(line(if correct {
+            Cell::new(format!("{CHECK} Correct"), Style::Green)
+        } else {
+            Cell::new(format!("{CROSS} Wrong"), Style::Red)
+        }));
+    }
+    match &stats.time {
+        Some(time) => {
+            details.push(Line::from(format!("Time: {}", short_duration(time.mean))));
+            if time.samples > 1 {
+                details.push(Line::from(format!(
+                    "  {} samples, min {}, median {}, p95 {}, stddev {}",
+                    time.samples,
+                    short_duration(time.min),
+                    short_duration(time.median),
+                    short_duration(time.p95),
+                    short_duration(time.stddev),
+                )));
+            }
+        }
+        None => details.push(Line::from("Time: not measured")),
+    }
+    if let Some(memory) = &stats.memory {
+        details.push(Line::from(format!(
+            "Heap: {} peak, {} in {} allocations",
+            Bytes(memory.peak),
+            Bytes(memory.total),
+            memory.allocations
+        )));
+    }
+    for comparison in &stats.baselines {
+        let mut cell = Cell::new(format!("Baseline {}:", comparison.name), Style::Bold);
+        if let Some(previous) = &comparison.previous_time {
+            cell = cell.push(format!(" {}", short_duration(previous.mean)), Style::Plain);
+        }
+        if let Some(previous) = &comparison.previous_memory {
+            cell = cell.push(format!(" {}", Bytes(previous.peak)), Style::Plain);
+        }
+        cell = match comparison.was_correct {
+            Some(true) => cell.push(format!(" {CHECK}"), Style::Green),
+            Some(false) => cell.push(format!(" {CROSS}"), Style::Red),
+            None => cell,
+        };
+        details.push(line(cell));
+    }
+}
+
+fn on_input_details(input: &str) -> String {
+    if input == DEFAULT_INPUT {
+        String::new()
+    } else {
+        format!(" on input {input}")
+    }
+}
+
+fn title(text: String) -> Line<'static> {
+    line(Cell::new(text, Style::Bold))
+}
+
+/// A cell of the terminal report, with the same styles
+fn line(Cell(spans): Cell) -> Line<'static> {
+    Line::from(
+        spans
+            .into_iter()
+            .map(|Span(text, style)| TuiSpan::styled(text, tui_style(style)))
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn tui_style(style: Style) -> TuiStyle {
+    let plain = TuiStyle::default();
+    match style {
+        Style::Plain => plain,
+        Style::Bold => plain.add_modifier(Modifier::BOLD),
+        Style::Dim => plain.add_modifier(Modifier::DIM),
+        Style::Green => plain.fg(Color::Green),
+        Style::Red => plain.fg(Color::Red),
+        Style::Yellow => plain.fg(Color::Yellow),
+        Style::Magenta => plain.fg(Color::Magenta),
+    }
+}
@@ -0,0 +1,294 @@
+//! Creation of the crates of new solutions
+//!
+//! The files of a new day are copied from `templates/<year>`, `templates/default` or the built-in
+//! template, the first that exists. The placeholders `{{year}}`, `{{day}}`, `{{name}}` and
+//! `{{dependencies}}` are replaced in all the text files, the others are copied as they are.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str,
+};
+
+use anyhow::{bail, Context};
+
+/// The template used if the workspace has none
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        r#"[package]
+name = "{{name}}"
+version = "0.1.0"
+edition = "2021"
+
+[package.metadata.aoc.part1]
+fun = "part1"
+
+[package.metadata.aoc.part2]
+fun = "part2"
+
+# [[package.metadata.aoc.examples]]
+# input = "example-inputs/1.txt"
+# part1 = 0
+
+[dependencies]
+runner = { path = "../../../runner" }
+{{dependencies}}"#,
+    ),
+    (
+        "src/lib.rs",
+        r#"pub fn part1(input: &str) -> usize {
+    input.len()
+}
+
+pub fn part2(input: &str) -> usize {
+    input.len()
+}
+
+runner::export_plugin!();
+"#,
+    ),
+    ("example-inputs/1.txt", ""),
+];
+
+/// Create the crate of a new day, its input, and register it in the library
+pub(crate) fn new_day(
+    workspace: &Path,
+    inputs: &Path,
+    year: u16,
+    day: u8,
+    commons: &[String],
+) -> anyhow::Result<()> {
+    let name = format!("aoc_{year}_{day}");
+    let dir = workspace
+        .join("solutions")
+        .join(year.to_string())
+        .join(day.to_string());
+    if dir.exists() {
+        bail!("{} already exists", dir.display())
+    }
+    let mut dependencies = String::new();
+    for krate in commons {
+        if !workspace
+            .join("commons")
+            .join(krate)
+            .join("Cargo.toml")
+            .is_file()
+        {
+            bail!("There is no crate {krate} in commons")
+        }
+        dependencies += &format!("{krate} = {{ path = \"../../../commons/{krate}\" }}\n");
+    }
+    let fill = |template: &str| {
+        template
+            .replace("{{year}}", &year.to_string())
+            .replace("{{day}}", &day.to_string())
+            .replace("{{name}}", &name)
+            .replace("{{dependencies}}", &dependencies)
+    };
+
+    let files = match [year.to_string().as_str(), "default"]
+        .into_iter()
+        .map(|name| workspace.join("templates").join(name))
+        .find(|template| template.is_dir())
+    {
+        Some(template) => {
+            log::info!("Using the template in {}", template.display());
+            let mut files = vec![];
+            read_template(&template, &template, &mut files)
+                .with_context(|| format!("Cannot read template {}", template.display()))?;
+            files
+        }
+        None => BUILTIN
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.as_bytes().to_vec()))
+            .collect(),
+    };
+    let has_dependencies = |content: &[u8]| {
+        str::from_utf8(content).is_ok_and(|content| content.contains("{{dependencies}}"))
+    };
+    if !commons.is_empty() && !files.iter().any(|(_, c)| has_dependencies(c)) {
+        log::warn!("The template has no {{{{dependencies}}}}, the commons are not added")
+    }
+    for (path, content) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap())
+            .with_context(|| format!("Cannot create {}", path.parent().unwrap().display()))?;
+        let content = match String::from_utf8(content) {
+            Ok(text) => fill(&text).into_bytes(),
+            Err(err) => err.into_bytes(),
+        };
+        fs::write(&path, content).with_context(|| format!("Cannot write {}", path.display()))?;
+    }
+    log::info!("Created {}", dir.display());
+
+    let input = inputs.join(year.to_string()).join(day.to_string());
+    if input.exists() {
+        log::info!("Keeping the existing input {}", input.display())
+    } else {
+        fs::create_dir_all(input.parent().unwrap())
+            .and_then(|()| fs::write(&input, ""))
+            .with_context(|| format!("Cannot create input {}", input.display()))?;
+        log::info!(
+            "Created the empty input {}, the day is skipped until it is filled",
+            input.display()
+        );
+    }
+
+    register(&workspace.join("library/Cargo.toml"), &name, year, day)
+        .context("Cannot register the solution in the library")?;
+    log::info!("Registered {name}, it will be run once the library is rebuilt");
+    Ok(())
+}
+
+/// Read all the files of a template, with their path relative to its root
+fn read_template(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<(PathBuf, Vec<u8>)>,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            read_template(root, &path, files)?
+        } else {
+            let content =
+                fs::read(&path).with_context(|| format!("Cannot read {}", path.display()))?;
+            files.push((path.strip_prefix(root)?.to_owned(), content))
+        }
+    }
+    Ok(())
+}
+
+/// Add the solution to the dependencies of the library, where its build script finds it
+fn register(manifest: &Path, name: &str, year: u16, day: u8) -> anyhow::Result<()> {
+    let content = fs::read_to_string(manifest)?;
+    let mut lines: Vec<&str> = content.lines().collect();
+    if lines
+        .iter()
+        .any(|line| line.split('=').next().map(str::trim) == Some(name))
+    {
+        return Ok(());
+    }
+    let start = lines
+        .iter()
+        .position(|line| line.trim() == "[dependencies]")
+        .context("The manifest has no [dependencies] table")?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |pos| start + 1 + pos);
+    // before the blank lines separating the next table
+    let at = (start + 1..end)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+        .map_or(start + 1, |i| i + 1);
+    let dependency = format!("{name} = {{ path = \"../solutions/{year}/{day}\" }}");
+    lines.insert(at, &dependency);
+    fs::write(manifest, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    /// A new directory, removed when dropped
+    struct TempDir(PathBuf);
+    impl TempDir {
+        fn new() -> Self {
+            // the tests run concurrently
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let count = COUNT.fetch_add(1, Ordering::Relaxed);
+            let dir = env::temp_dir().join(format!("aoc-scaffold-{}-{count}", process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const MANIFEST: &str = r#"[package]
+name = "library"
+
+[dependencies]
+runner = { path = "../runner" }
+aoc_2015_1 = { path = "../solutions/2015/1" }
+
+[build-dependencies]
+discovery = { path = "../discovery" }
+"#;
+
+    #[test]
+    fn registers_new_members() {
+        let dir = TempDir::new();
+        let manifest = dir.0.join("Cargo.toml");
+        fs::write(&manifest, MANIFEST).unwrap();
+
+        register(&manifest, "aoc_2015_2", 2015, 2).unwrap();
+        let expected = MANIFEST.replace(
+            "\n\n[build-dependencies]",
+            "\naoc_2015_2 = { path = \"../solutions/2015/2\" }\n\n[build-dependencies]",
+        );
+        assert_eq!(fs::read_to_string(&manifest).unwrap(), expected);
+        // already registered
+        register(&manifest, "aoc_2015_2", 2015, 2).unwrap();
+        register(&manifest, "aoc_2015_1", 2015, 1).unwrap();
+        assert_eq!(fs::read_to_string(&manifest).unwrap(), expected);
+
+        // at the end of the last table
+        fs::write(
+            &manifest,
+            "[dependencies]\nrunner = { path = \"../runner\" }",
+        )
+        .unwrap();
+        register(&manifest, "aoc_2015_2", 2015, 2).unwrap();
+        assert_eq!(
+            fs::read_to_string(&manifest).unwrap(),
+            "[dependencies]\nrunner = { path = \"../runner\" }\n\
+             aoc_2015_2 = { path = \"../solutions/2015/2\" }\n"
+        );
+
+        fs::write(&manifest, "[package]\nname = \"library\"\n").unwrap();
+        let err = register(&manifest, "aoc_2015_2", 2015, 2).unwrap_err();
+        assert_eq!(err.to_string(), "The manifest has no [dependencies] table");
+    }
+
+    #[test]
+    fn templates() {
+        let workspace = TempDir::new();
+        let template = workspace.0.join("templates/default");
+        fs::create_dir_all(template.join("src")).unwrap();
+        fs::create_dir_all(workspace.0.join("library")).unwrap();
+        fs::write(workspace.0.join("library/Cargo.toml"), MANIFEST).unwrap();
+        fs::write(
+            template.join("src/lib.rs"),
+            "// {{year}}/{{day}} in {{name}}\n",
+        )
+        .unwrap();
+        let binary = [0xff, 0xfe, b'{', b'{', b'd', b'a', b'y', b'}', b'}'];
+        fs::write(template.join("data.bin"), binary).unwrap();
+
+        let inputs = workspace.0.join("inputs");
+        new_day(&workspace.0, &inputs, 2016, 3, &[]).unwrap();
+        let dir = workspace.0.join("solutions/2016/3");
+        assert_eq!(
+            fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+            "// 2016/3 in aoc_2016_3\n"
+        );
+        assert_eq!(fs::read(dir.join("data.bin")).unwrap(), binary);
+        assert_eq!(fs::read(inputs.join("2016/3")).unwrap(), b"");
+
+        let err = new_day(&workspace.0, &inputs, 2016, 3, &[]).unwrap_err();
+        assert!(err.to_string().ends_with("already exists"), "{err}");
+    }
+}
@@ -0,0 +1,281 @@
+//! Schema of the JSON report
+//!
+//! These types are what `--format json` serializes, and can be used to deserialize the report in
+//! other tools. Any incompatible change to them must bump [`SCHEMA_VERSION`].
+//!
+//! All durations are in nanoseconds, and all sizes in bytes.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the schema
+pub const SCHEMA_VERSION: u32 = 7;
+
+/// A full report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// Version of the schema, always [`SCHEMA_VERSION`] when produced by this crate
+    pub schema_version: u32,
+    /// Name and version of the runner that produced the report
+    pub generator: String,
+    /// Query selecting the problems, in the same syntax used on the command line
+    pub filters: String,
+    /// Results of the examples
+    #[serde(default)]
+    pub examples: Vec<Example>,
+    /// Combined statistics of all the parts, if at least one was run
+    pub totals: Option<Stats>,
+    /// Results of each year
+    pub years: BTreeMap<u16, Year>,
+}
+
+/// Results of an example
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Example {
+    pub year: u16,
+    pub day: u8,
+    pub name: String,
+    /// Results of the two parts, `null` if the part has no expected answer on this example
+    pub parts: [Option<ExamplePart>; 2],
+}
+
+/// Result of a part on an example
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ExamplePart {
+    /// The solution gave an answer
+    Answered {
+        answer: String,
+        expected: String,
+        correct: bool,
+        /// Parameters of the part on the example, if it has any
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        params: BTreeMap<String, String>,
+    },
+    /// The solution failed to give an answer
+    Failed {
+        expected: String,
+        kind: FailureKind,
+        message: String,
+        /// Frames of the panic, if it panicked
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backtrace: Option<String>,
+        /// Parameters of the part on the example, if it has any
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        params: BTreeMap<String, String>,
+    },
+}
+
+/// Results of a year
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Year {
+    /// Combined statistics of all the parts of the year, if at least one was run
+    pub totals: Option<Stats>,
+    /// Results of each day
+    pub days: BTreeMap<u8, Day>,
+}
+
+/// Results of a day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Day {
+    /// The solutions were run
+    Run(Box<DayRun>),
+    /// The day has no input, so it was skipped
+    MissingInput,
+    /// The input of the day is empty, waiting to be filled, so it was skipped
+    EmptyInput {
+        /// Path of the empty input
+        path: String,
+    },
+    /// The input could not be read
+    InputError {
+        /// Description of the error
+        error: String,
+    },
+}
+
+/// Results of a day whose solutions were run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayRun {
+    /// Results on each input, by name
+    ///
+    /// A day with a single input file has only the `default` input.
+    pub inputs: BTreeMap<String, DayInput>,
+    /// Combined statistics of the parsing step over all the inputs, absent if the day has
+    /// none or it never succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse: Option<Stats>,
+    /// Combined statistics of each part over all the inputs, `null` if the part never
+    /// succeeded
+    ///
+    /// A part that failed on any input is not correct.
+    pub parts: [Option<Stats>; 2],
+    /// Combined statistics of the parts of the day, including the parsing step, if at least
+    /// one succeeded
+    pub totals: Option<Stats>,
+}
+
+/// Results of a day on a single input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayInput {
+    /// Result of the parsing step shared by the parts, absent if the day has none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse: Option<Parse>,
+    /// Results of the two parts, `null` if the part was not run
+    ///
+    /// The timings of the parts do not include the parsing step.
+    pub parts: [Option<Part>; 2],
+    /// Combined statistics of the parts on this input, including the parsing step, if at least
+    /// one succeeded
+    pub totals: Option<Stats>,
+}
+
+/// Result of the parsing step of a day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Parse {
+    /// The input was parsed
+    Parsed {
+        /// Timings, the parsing step is always correct
+        stats: Stats,
+    },
+    /// The parsing step failed
+    Failed {
+        kind: FailureKind,
+        message: String,
+        /// Frames of the panic, if it panicked
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backtrace: Option<String>,
+    },
+}
+
+/// Result of a part
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Part {
+    /// The solution gave an answer
+    Answered {
+        /// The answer given
+        answer: String,
+        /// The answer is a multiline drawing
+        multiline: bool,
+        /// The known correct answer, if any
+        expected: Option<String>,
+        /// Why the answer is certainly wrong while the correct one is unknown, e.g. an answer
+        /// already rejected by the site, absent if not known to be wrong this way
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rejection: Option<String>,
+        /// Correctness and timings
+        stats: Stats,
+    },
+    /// The solution failed to give an answer
+    Failed {
+        /// Why the solution failed
+        kind: FailureKind,
+        /// Human readable description of the failure, e.g. the panic message or the error chain
+        message: String,
+        /// Frames of the panic, from the panicking function to the runner, if it panicked
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backtrace: Option<String>,
+    },
+}
+
+/// Reasons a solution can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    Panic,
+    /// The solution returned an error, the message has one line per source of the error
+    Error,
+    Timeout,
+    StackOverflow,
+    OutOfMemory,
+    Aborted,
+    Signal,
+    Exited,
+    /// The child process for an isolated solution could not be run
+    Spawn,
+}
+
+/// Correctness and timings of a part, or a sum of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    /// If the answer is correct, `null` if the correct answer is unknown
+    ///
+    /// For sums, `true` only if all the answers are known and correct.
+    pub correct: Option<bool>,
+    /// Running time, `null` if not measured
+    pub time: Option<TimeStats>,
+    /// Heap usage, absent if not measured
+    ///
+    /// For sums, the peak is the highest of the peaks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryStats>,
+    /// Comparisons with each baseline, in the order they were given
+    pub baselines: Vec<BaselineComparison>,
+}
+
+/// Comparison with a baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineComparison {
+    /// Name of the baseline
+    pub name: String,
+    /// If the answer in the baseline was correct, `null` if unknown
+    pub was_correct: Option<bool>,
+    /// Comparison with the running time in the baseline, `null` if either is missing
+    pub time: Option<BaselineDelta>,
+    /// Comparison with the heap usage in the baseline, absent if either is missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryDelta>,
+}
+
+/// Statistics on a running time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeStats {
+    /// Number of samples, if less than 2 the standard deviation is meaningless
+    pub samples: u32,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub stddev_ns: u64,
+    pub p95_ns: u64,
+}
+
+/// Comparison of a running time with the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BaselineDelta {
+    /// Running time in the baseline
+    pub previous: TimeStats,
+    /// Difference of the means, positive if slower than the baseline
+    pub delta_ns: i64,
+    /// The difference is statistically significant
+    pub significant: bool,
+}
+
+/// Heap usage of a part, counted by the allocator of the runner
+///
+/// Only the allocations of the thread running the part are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// Maximum size of the heap in use at the same time
+    pub peak_bytes: u64,
+    /// Size of all the allocations, including the ones already freed
+    pub total_bytes: u64,
+    /// Number of allocations, reallocations included
+    pub allocations: u64,
+}
+
+/// Comparison of a heap usage with the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryDelta {
+    /// Heap usage in the baseline
+    pub previous: MemoryStats,
+    /// Difference of the peaks, positive if more than the baseline
+    pub delta_peak_bytes: i64,
+    /// Difference of the total sizes allocated
+    pub delta_total_bytes: i64,
+    /// Difference of the numbers of allocations
+    pub delta_allocations: i64,
+}
@@ -0,0 +1,509 @@
+//! Interactive terminal UI browsing the results
+//!
+//! The parts run in the background on their inputs, and the rows of the years, days and parts are
+//! updated as their results come in. The selected row is detailed in a side pane, with the whole
+//! answer, the failure or the comparison with the baseline. The examples are not run.
+//!
+//! Keys: up and down to move, left and right or enter to fold the rows, `r` to run the selection
+//! again, `b` to switch the baseline compared against, `a` to accept the answer of the selected
+//! part into the answers file, and `q` to quit.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::Context;
+use itertools::Itertools;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Row, Table, TableState, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    answers::{self, Answers, PartSpec},
+    bench::BenchSettings,
+    history::Trends,
+    isolation::Isolation,
+    measure_phase,
+    report::{RowKey, TuiRow},
+    Baselines, ExampleMeasures, FailedMeasurements, InputMeasures, Inputs, Library, Measurements,
+    Measures, Phase, Report,
+};
+
+/// How long to wait for a key before looking for new results
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A phase of a day on an input
+type Step = (u16, u8, String, Phase);
+
+/// What a worker tells the UI
+enum Update {
+    Started(Step),
+    Done(Step, Result<Measurements, FailedMeasurements>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pending {
+    Queued,
+    Running,
+}
+
+/// Everything the runs need
+pub(crate) struct Tui<'a> {
+    pub(crate) library: &'a Library,
+    pub(crate) inputs: &'a Path,
+    pub(crate) skip_missing: bool,
+    pub(crate) bench: Option<BenchSettings>,
+    pub(crate) jobs: NonZeroUsize,
+    pub(crate) isolation: Isolation<'a>,
+    pub(crate) answers_file: &'a Path,
+    pub(crate) trends: &'a Trends,
+}
+impl Tui<'_> {
+    /// Run the UI until the user quits
+    pub(crate) fn run(
+        &self,
+        answers: Answers,
+        baselines: Vec<(String, Baselines)>,
+    ) -> anyhow::Result<()> {
+        let inputs = self.library.read_inputs(self.inputs, self.skip_missing);
+        let parts: BTreeMap<_, _> = (self.library.solutions.values())
+            .flat_map(|y| y.solutions.values())
+            .flat_map(|d| {
+                d.phases()
+                    .map(|(phase, part)| ((d.day.0, d.day.1, phase), part))
+            })
+            .collect();
+        // long running parts first, so they do not end up trailing the others
+        let steps: Vec<Step> = inputs
+            .iter()
+            .filter_map(|(&(y, d), inputs)| Some(((y, d), inputs.as_ref().ok()?)))
+            .flat_map(|((y, d), inputs)| {
+                let parts = &parts;
+                inputs.keys().flat_map(move |name| {
+                    (parts.range((y, d, Phase::Parse)..=(y, d, Phase::Part(2))))
+                        .map(move |(&(y, d, phase), part)| ((y, d, name.clone(), phase), part))
+                })
+            })
+            .sorted_by_key(|(_, part)| !part.long_running)
+            .map(|(step, _)| step)
+            .collect();
+
+        // the logs of the parts would draw over the UI, their panics are captured
+        let log_level = log::max_level();
+        log::set_max_level(log::LevelFilter::Off);
+
+        let (bench, isolation) = (self.bench, self.isolation);
+        let (task_tx, task_rx) = mpsc::channel::<Step>();
+        let task_rx = Mutex::new(task_rx);
+        let (update_tx, update_rx) = mpsc::channel();
+        let quit = AtomicBool::new(false);
+        let timing_lock = Mutex::new(());
+        let result = thread::scope(|s| {
+            for _ in 0..self.jobs.get() {
+                let update_tx = update_tx.clone();
+                let (inputs, parts, task_rx) = (&inputs, &parts, &task_rx);
+                let (quit, timing_lock) = (&quit, &timing_lock);
+                s.spawn(move || loop {
+                    let Ok(step) = task_rx.lock().unwrap().recv() else {
+                        break;
+                    };
+                    if quit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let (y, d, name, phase) = &step;
+                    let input = &inputs[&(*y, *d)].as_ref().unwrap()[name];
+                    let _ = update_tx.send(Update::Started(step.clone()));
+                    let result = measure_phase(
+                        (*y, *d, name, *phase),
+                        parts[&(*y, *d, *phase)],
+                        input,
+                        bench,
+                        isolation,
+                        timing_lock,
+                    );
+                    let _ = update_tx.send(Update::Done(step, result));
+                });
+            }
+
+            let mut app = App {
+                tui: self,
+                inputs: &inputs,
+                phases: parts.keys().cloned().collect(),
+                results: BTreeMap::new(),
+                pending: BTreeMap::new(),
+                answers,
+                baselines,
+                baseline: 0,
+                rows: vec![],
+                collapsed: BTreeSet::new(),
+                selected: None,
+                table: TableState::default(),
+                message: None,
+                tasks: task_tx,
+            };
+            for step in steps {
+                app.queue(step);
+            }
+            app.rebuild();
+            let mut terminal = ratatui::init();
+            let result = app.run(&mut terminal, &update_rx);
+            ratatui::restore();
+
+            quit.store(true, Ordering::Relaxed);
+            log::set_max_level(log_level);
+            if app.pending.values().any(|p| *p == Pending::Running) {
+                log::info!("Waiting for the running parts to finish");
+            }
+            // the workers stop once the queue is closed
+            drop(app);
+            result
+        });
+        result
+    }
+}
+
+struct App<'a> {
+    tui: &'a Tui<'a>,
+    inputs: &'a Inputs,
+    /// The phases of the days in the library
+    phases: BTreeSet<(u16, u8, Phase)>,
+    results: BTreeMap<Step, Result<Measurements, FailedMeasurements>>,
+    pending: BTreeMap<Step, Pending>,
+    answers: Answers,
+    baselines: Vec<(String, Baselines)>,
+    /// Index of the baseline compared against, none past the last one
+    baseline: usize,
+    rows: Vec<TuiRow>,
+    collapsed: BTreeSet<RowKey>,
+    selected: Option<RowKey>,
+    table: TableState,
+    /// Outcome of the last action
+    message: Option<Line<'static>>,
+    tasks: mpsc::Sender<Step>,
+}
+impl App<'_> {
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        updates: &mpsc::Receiver<Update>,
+    ) -> anyhow::Result<()> {
+        loop {
+            let mut changed = false;
+            while let Ok(update) = updates.try_recv() {
+                match update {
+                    Update::Started(step) => {
+                        self.pending.insert(step, Pending::Running);
+                    }
+                    Update::Done(step, result) => {
+                        self.pending.remove(&step);
+                        self.results.insert(step, result);
+                        changed = true;
+                    }
+                }
+            }
+            if changed {
+                self.rebuild();
+            }
+            terminal
+                .draw(|frame| self.draw(frame))
+                .context("Cannot draw the UI")?;
+            if event::poll(POLL_INTERVAL).context("Cannot read the keys")? {
+                if let Event::Key(key) = event::read().context("Cannot read the keys")? {
+                    if key.kind == KeyEventKind::Press && !self.key(key.code) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    fn queue(&mut self, step: Step) {
+        if !self.pending.contains_key(&step) {
+            self.pending.insert(step.clone(), Pending::Queued);
+            let _ = self.tasks.send(step);
+        }
+    }
+
+    /// Build the rows again from the results
+    fn rebuild(&mut self) {
+        let mut measures = Measures::new();
+        for (&(y, d), inputs) in self.inputs {
+            let day = match inputs {
+                Ok(inputs) => Ok(inputs
+                    .keys()
+                    .map(|name| {
+                        let result =
+                            |phase| self.results.get(&(y, d, name.clone(), phase)).cloned();
+                        let measures = InputMeasures {
+                            parse: result(Phase::Parse).map(|r| r.map(|m| m.time)),
+                            parts: [1, 2].map(|p| result(Phase::Part(p))),
+                        };
+                        (name.clone(), measures)
+                    })
+                    .collect()),
+                Err(err) => Err(err.clone()),
+            };
+            measures.entry(y).or_default().insert(d, day);
+        }
+        let report = Report::new(
+            self.tui.library.filters.clone(),
+            measures,
+            ExampleMeasures::new(),
+            self.answers.clone(),
+            self.baselines
+                .get(self.baseline)
+                .cloned()
+                .into_iter()
+                .collect(),
+            self.tui.trends,
+        );
+        // the report has both parts of all the days
+        self.rows = report
+            .tui_rows()
+            .into_iter()
+            .filter(|row| match &row.key {
+                RowKey::Step(y, d, _, phase) => self.phases.contains(&(*y, *d, *phase)),
+                _ => true,
+            })
+            .collect();
+    }
+
+    fn is_visible(&self, key: &RowKey) -> bool {
+        let mut parent = key.parent();
+        while let Some(key) = parent {
+            if self.collapsed.contains(&key) {
+                return false;
+            }
+            parent = key.parent();
+        }
+        true
+    }
+
+    fn visible_rows(&self) -> Vec<&TuiRow> {
+        self.rows
+            .iter()
+            .filter(|r| self.is_visible(&r.key))
+            .collect()
+    }
+
+    /// Index of the selected row among the visible ones, or of its first visible parent
+    fn selected_index(&self, rows: &[&TuiRow]) -> Option<usize> {
+        let mut key = self.selected.clone();
+        while let Some(k) = key {
+            if let Some(index) = rows.iter().position(|r| r.key == k) {
+                return Some(index);
+            }
+            key = k.parent();
+        }
+        (!rows.is_empty()).then_some(0)
+    }
+
+    /// Handle a key, returning `false` to quit
+    fn key(&mut self, code: KeyCode) -> bool {
+        let rows = self.visible_rows();
+        let index = self.selected_index(&rows);
+        let selected = index.map(|i| rows[i].key.clone());
+        let move_to = |i: usize| Some(rows[i.min(rows.len().saturating_sub(1))].key.clone());
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = index.and_then(|i| move_to(i.saturating_sub(1)))
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = index.and_then(|i| move_to(i + 1))
+            }
+            KeyCode::PageUp => self.selected = index.and_then(|i| move_to(i.saturating_sub(20))),
+            KeyCode::PageDown => self.selected = index.and_then(|i| move_to(i + 20)),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = index.and_then(|_| move_to(0)),
+            KeyCode::End | KeyCode::Char('G') => {
+                self.selected = index.and_then(|_| move_to(usize::MAX))
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                if let Some(key) = selected {
+                    let foldable = !matches!(key, RowKey::Step(..));
+                    if foldable && !self.collapsed.contains(&key) {
+                        self.collapsed.insert(key);
+                    } else if let Some(parent) = key.parent() {
+                        self.selected = Some(parent);
+                    }
+                }
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                if let Some(key) = selected {
+                    self.collapsed.remove(&key);
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(key) = selected.filter(|k| !matches!(k, RowKey::Step(..))) {
+                    if !self.collapsed.remove(&key) {
+                        self.collapsed.insert(key);
+                    }
+                }
+            }
+            KeyCode::Char('r') => {
+                if let Some(key) = selected {
+                    self.run_again(&key)
+                }
+            }
+            KeyCode::Char('b') => {
+                self.baseline = (self.baseline + 1) % (self.baselines.len() + 1);
+                self.message = Some(Line::from(match self.baselines.get(self.baseline) {
+                    Some((name, _)) => format!("Comparing against {name}"),
+                    None => "Not comparing against a baseline".to_owned(),
+                }));
+                self.rebuild();
+            }
+            KeyCode::Char('a') => {
+                if let Some(key) = selected {
+                    self.accept(&key)
+                }
+            }
+            _ => (),
+        }
+        true
+    }
+
+    /// Queue the steps of the selected row
+    fn run_again(&mut self, key: &RowKey) {
+        let steps: Vec<Step> = (self.rows.iter())
+            .filter_map(|row| match &row.key {
+                RowKey::Step(y, d, name, phase) => {
+                    let selected = match key {
+                        RowKey::Year(year) => y == year,
+                        RowKey::Day(year, day) => (y, d) == (year, day),
+                        RowKey::Step(..) => row.key == *key,
+                    };
+                    selected.then(|| (*y, *d, name.clone(), *phase))
+                }
+                _ => None,
+            })
+            .collect();
+        self.message = Some(Line::from(format!("Running {} steps again", steps.len())));
+        for step in steps {
+            self.queue(step);
+        }
+    }
+
+    /// Accept the answer of the selected part
+    fn accept(&mut self, key: &RowKey) {
+        let RowKey::Step(y, d, name, Phase::Part(p)) = key else {
+            self.message = Some(error("Select a part to accept its answer"));
+            return;
+        };
+        let Some(Ok(result)) = self.results.get(&(*y, *d, name.clone(), Phase::Part(*p))) else {
+            self.message = Some(error("The part has no answer"));
+            return;
+        };
+        let spec = PartSpec::new(*y, *d, *p);
+        let computed = BTreeMap::from([(name.clone(), result.answer.clone())]);
+        match answers::record(self.tui.answers_file, spec, computed, answers::Edit::Accept) {
+            Ok(answers) => {
+                self.answers = answers;
+                self.message = Some(Line::from(format!("Accepted the answer of {spec}")));
+                self.rebuild();
+            }
+            Err(err) => self.message = Some(error(format!("{err:#}"))),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [table_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(main);
+
+        let rows = self.visible_rows();
+        let index = self.selected_index(&rows);
+        let mut widths = [0; 4];
+        let table_rows: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                let mut cells = row.cells.clone();
+                if let RowKey::Step(y, d, name, phase) = &row.key {
+                    match self.pending.get(&(*y, *d, name.clone(), *phase)) {
+                        Some(Pending::Queued) => cells[2] = Line::styled("queued", dim()),
+                        Some(Pending::Running) => {
+                            cells[2] = Line::styled("running", Style::new().fg(Color::Yellow))
+                        }
+                        None => (),
+                    }
+                }
+                for (width, cell) in widths.iter_mut().zip(&cells) {
+                    *width = (*width).max(cell.width() as u16);
+                }
+                Row::new(cells)
+            })
+            .collect();
+        let details = index.map_or_else(Vec::new, |i| rows[i].details.clone());
+        let title = format!(" Report for the query \"{}\" ", self.tui.library.filters);
+        let table = Table::new(
+            table_rows,
+            [
+                Constraint::Length(widths[0]),
+                Constraint::Fill(1),
+                Constraint::Length(widths[2]),
+                Constraint::Length(widths[3].max(2)),
+            ],
+        )
+        .header(
+            Row::new(["", "Answer", "Time", "Ok"]).style(Style::new().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(Block::bordered().title(title));
+        self.table.select(index);
+        frame.render_stateful_widget(table, table_area, &mut self.table);
+
+        frame.render_widget(
+            Paragraph::new(details)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(" Details ")),
+            details_area,
+        );
+
+        let running = self
+            .pending
+            .values()
+            .filter(|p| **p == Pending::Running)
+            .count();
+        let queued = self.pending.len() - running;
+        let mut spans = vec![Span::raw(if self.pending.is_empty() {
+            "Done".to_owned()
+        } else {
+            format!("{running} running, {queued} queued")
+        })];
+        if let Some((name, _)) = self.baselines.get(self.baseline) {
+            spans.push(Span::raw(format!(" │ vs {name}")));
+        }
+        if let Some(message) = &self.message {
+            spans.push(Span::raw(" │ "));
+            spans.extend(message.spans.iter().cloned());
+        }
+        spans.push(Span::styled(
+            " │ ↑↓ move  ←→ fold  r run again  b baseline  a accept  q quit",
+            dim(),
+        ));
+        frame.render_widget(Line::from(spans), status);
+    }
+}
+
+fn dim() -> Style {
+    Style::new().add_modifier(Modifier::DIM)
+}
+
+fn error(message: impl Into<String>) -> Line<'static> {
+    Line::styled(message.into(), Style::new().fg(Color::Red))
+}
@@ -0,0 +1,219 @@
+//! Rerunning the selected days when they change
+//!
+//! The crates of the days in `./solutions`, their inputs and the answers are polled for changes.
+//! Once the changes settle, the changed crates are rebuilt as plugins with
+//! `cargo rustc --crate-type cdylib`, in the profile of the running binary, and replace their days
+//! in the library. The library is then run again. If a build fails nothing is run, so the last
+//! report stays visible until the error is fixed.
+
+use std::{
+    collections::BTreeMap,
+    env::{self, consts::DLL_EXTENSION, current_exe},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    slice, thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+
+use crate::{plugin, Library};
+
+/// How often the watched files are polled
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Time without changes before acting on them, as editors can save in multiple writes
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// What is watched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Source {
+    /// The crate of a day, that must be rebuilt
+    Crate(u16, u8),
+    /// The input of a day
+    Input(u16, u8),
+    Answers,
+}
+
+/// Modification times of the watched files, by source
+type Snapshot = BTreeMap<Source, BTreeMap<PathBuf, SystemTime>>;
+
+/// Run the library, then again every time the selected days change
+///
+/// `run` is given the plugins loaded so far, including the rebuilt days, as the isolated parts
+/// have to load them again. This only returns on errors.
+pub(crate) fn watch<R>(
+    mut library: Library,
+    inputs: &Path,
+    answers: &Path,
+    plugins: &[PathBuf],
+    mut run: R,
+) -> anyhow::Result<()>
+where
+    R: FnMut(&Library, &[PathBuf]) -> anyhow::Result<()>,
+{
+    let mut sources = vec![(Source::Answers, answers.to_owned())];
+    let days = library
+        .solutions
+        .values()
+        .flat_map(|y| y.solutions.values());
+    for solution in days {
+        let (year, day) = solution.day;
+        let dir = Path::new("solutions")
+            .join(year.to_string())
+            .join(day.to_string());
+        if !dir.join("Cargo.toml").is_file() {
+            bail!(
+                "Cannot find the crate of {year}::{day} in {}, the watch mode must be run at the \
+                 root of the workspace",
+                dir.display()
+            )
+        }
+        sources.push((Source::Crate(year, day), dir));
+        for path in solution.input_paths(inputs) {
+            sources.push((Source::Input(year, day), path));
+        }
+    }
+    let profile = profile_args()?;
+    let mut plugins = plugins.to_vec();
+
+    let mut last = snapshot(&sources);
+    run(&library, &plugins)?;
+    loop {
+        log::info!("Watching for changes");
+        let mut current = last.clone();
+        while current == last {
+            thread::sleep(POLL_INTERVAL);
+            current = snapshot(&sources);
+        }
+        loop {
+            thread::sleep(DEBOUNCE);
+            let settled = snapshot(&sources);
+            if settled == current {
+                break;
+            }
+            current = settled;
+        }
+
+        let mut failed = false;
+        for (source, dir) in &sources {
+            let Source::Crate(year, day) = source else {
+                continue;
+            };
+            if current.get(source) == last.get(source) {
+                continue;
+            }
+            log::info!("Rebuilding {year}::{day}");
+            let rebuilt = build(&dir.join("Cargo.toml"), &profile).and_then(|plugin| {
+                plugin::load(slice::from_ref(&plugin), &mut library)?;
+                Ok(plugin)
+            });
+            match rebuilt {
+                Ok(plugin) => {
+                    if !plugins.contains(&plugin) {
+                        plugins.push(plugin)
+                    }
+                }
+                Err(err) => {
+                    log::error!("Cannot rebuild {year}::{day}: {err:?}");
+                    failed = true;
+                }
+            }
+        }
+        last = current;
+        if failed {
+            log::warn!("Keeping the last report until the build is fixed");
+            continue;
+        }
+        run(&library, &plugins)?;
+    }
+}
+
+/// The modification times of all the files of the sources
+fn snapshot(sources: &[(Source, PathBuf)]) -> Snapshot {
+    fn add(path: &Path, times: &mut BTreeMap<PathBuf, SystemTime>) {
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+        if !metadata.is_dir() {
+            if let Ok(modified) = metadata.modified() {
+                times.insert(path.to_owned(), modified);
+            }
+            return;
+        }
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            // skipping the build directories and the hidden files, like the ones left by editors
+            let name = entry.file_name();
+            if name == "target" || name.to_string_lossy().starts_with('.') {
+                continue;
+            }
+            add(&entry.path(), times)
+        }
+    }
+
+    // the inputs of a day can be in multiple places
+    let mut snapshot = Snapshot::new();
+    for (source, path) in sources {
+        add(path, snapshot.entry(*source).or_default());
+    }
+    snapshot
+}
+
+/// The arguments of cargo selecting the profile of the running binary
+///
+/// The profile is the name of the directory of the binary, e.g. `target/release`.
+fn profile_args() -> anyhow::Result<Vec<String>> {
+    let exe = current_exe().context("Cannot find the running binary")?;
+    let profile = exe
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(OsStr::to_str)
+        .context("Cannot find the profile of the running binary")?;
+    Ok(match profile {
+        "debug" => vec![],
+        "release" => vec!["--release".to_owned()],
+        profile => vec!["--profile".to_owned(), profile.to_owned()],
+    })
+}
+
+/// A line of the JSON output of cargo
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    #[serde(default)]
+    manifest_path: PathBuf,
+    #[serde(default)]
+    filenames: Vec<PathBuf>,
+}
+
+/// Build a crate as a plugin, returning the path of the dynamic library
+///
+/// The diagnostics are shown as cargo renders them.
+fn build(manifest: &Path, profile: &[String]) -> anyhow::Result<PathBuf> {
+    let output = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .arg("rustc")
+        .arg("--manifest-path")
+        .arg(manifest)
+        .args(profile)
+        .args(["--crate-type", "cdylib"])
+        .args(["--message-format", "json-render-diagnostics"])
+        .stderr(Stdio::inherit())
+        .output()
+        .context("Cannot run cargo")?;
+    if !output.status.success() {
+        bail!("cargo failed ({})", output.status)
+    }
+    let manifest = manifest.canonicalize()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Message>(line).ok())
+        .filter(|msg| msg.reason == "compiler-artifact" && msg.manifest_path == manifest)
+        .flat_map(|msg| msg.filenames)
+        .find(|file| file.extension() == Some(OsStr::new(DLL_EXTENSION)))
+        .context("cargo did not build a dynamic library")
+}
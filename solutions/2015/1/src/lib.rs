@@ -26,6 +26,8 @@ pub fn part2(input: &str) -> usize {
         + 1
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod examples {
     #[test]
@@ -107,6 +107,8 @@ pub fn part2(input: &str) -> usize {
     seq.len()
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod test {
     use crate::look_and_say;
@@ -77,6 +77,8 @@ pub fn part2(input: &str) -> [u8; 8] {
     first_valid(step(part1(input)))
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod tests {
     use crate::{parse, part1};
@@ -465,3 +465,5 @@ impl<'de> Deserialize<'de> for NumberCount {
 pub fn part2(input: &str) -> isize {
     serde_json::from_str::<NumberCount>(input).unwrap().0
 }
+
+runner::export_plugin!();
@@ -80,3 +80,5 @@ pub fn part2(input: &str) -> isize {
     input.push_row(vec![0; input.cols()]);
     longest_circular(&input)
 }
+
+runner::export_plugin!();
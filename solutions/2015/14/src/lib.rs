@@ -102,3 +102,5 @@ pub fn part2(input: &str) -> usize {
     }
     runners.into_iter().map(|(_, p)| p).max().unwrap()
 }
+
+runner::export_plugin!();
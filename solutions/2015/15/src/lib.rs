@@ -205,6 +205,8 @@ fn specialized_part_2_dishomogeneus(
     max
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod tests {
 
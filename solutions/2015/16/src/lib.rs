@@ -136,3 +136,5 @@ pub fn part2(input: &str) -> usize {
         .unwrap()
         .num
 }
+
+runner::export_plugin!();
@@ -55,3 +55,5 @@ pub fn part2(input: &str) -> usize {
     containers.sort_unstable_by_key(|n| Reverse(*n));
     count_min(&containers, 150, 0, usize::MAX).1
 }
+
+runner::export_plugin!();
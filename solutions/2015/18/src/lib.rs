@@ -75,3 +75,5 @@ pub fn part2(input: &str) -> usize {
     current[100][100] = true;
     current.into_iter().flatten().filter(|x| *x).count()
 }
+
+runner::export_plugin!();
@@ -98,3 +98,5 @@ pub fn part1(input: &str) -> usize {
     }
     subbed.len()
 }
+
+runner::export_plugin!();
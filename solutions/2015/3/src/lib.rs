@@ -1,4 +1,3 @@
-#![feature(array_chunks)]
 use std::collections::BTreeSet;
 
 pub fn part1(input: &str) -> usize {
@@ -24,8 +23,8 @@ pub fn part2(input: &str) -> usize {
     let mut y = 0isize;
     let mut rx = 0isize;
     let mut ry = 0isize;
-    let mut chunkes = input.as_bytes().array_chunks();
-    for [cmd, rcmd] in &mut chunkes {
+    let (chunkes, remainder) = input.as_bytes().as_chunks();
+    for [cmd, rcmd] in chunkes {
         match cmd {
             b'>' => x += 1,
             b'<' => x -= 1,
@@ -43,7 +42,7 @@ pub fn part2(input: &str) -> usize {
         }
         visited.insert([rx, ry]);
     }
-    if let [cmd] = chunkes.remainder() {
+    if let [cmd] = remainder {
         match cmd {
             b'>' => x += 1,
             b'<' => x -= 1,
@@ -55,3 +54,5 @@ pub fn part2(input: &str) -> usize {
     }
     visited.len()
 }
+
+runner::export_plugin!();
@@ -21,3 +21,5 @@ fn mine<const N_HALF: usize, const N_ODD: bool>(input: &str) -> usize {
     }
     unreachable!()
 }
+
+runner::export_plugin!();
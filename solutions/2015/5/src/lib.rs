@@ -30,6 +30,8 @@ pub fn part2(input: &str) -> usize {
     input.lines().filter(|l| is_nicer(l)).count()
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod examples {
     use crate::{is_nice, is_nicer};
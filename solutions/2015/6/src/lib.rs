@@ -137,3 +137,5 @@ pub fn part2(input: &str) -> usize {
     }
     rects.into_iter().map(|(s, r)| s * r.area()).sum()
 }
+
+runner::export_plugin!();
@@ -113,3 +113,5 @@ pub fn part2(input: &str) -> u16 {
     *input.wires.get_mut("b").unwrap() = Expr::Const(Operand::Const(a));
     input.eval("a", &mut BTreeMap::new())
 }
+
+runner::export_plugin!();
@@ -31,3 +31,5 @@ pub fn part2(input: &str) -> usize {
         .map(|l| 2 + l.bytes().filter(|a| *a == b'\\' || *a == b'"').count())
         .sum()
 }
+
+runner::export_plugin!();
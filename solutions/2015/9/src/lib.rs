@@ -122,3 +122,5 @@ pub fn part2(input: &str) -> usize {
     }
     max
 }
+
+runner::export_plugin!();
@@ -36,3 +36,5 @@ pub fn part2(input: &str) -> i64 {
     }
     largest_three.into_iter().sum()
 }
+
+runner::export_plugin!();
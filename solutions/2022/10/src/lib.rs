@@ -117,3 +117,5 @@ pub fn part2(input: &str) -> String {
     }
     screen
 }
+
+runner::export_plugin!();
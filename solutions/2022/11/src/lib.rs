@@ -199,3 +199,5 @@ pub fn part2(input: &str) -> usize {
     }
     monkeys.monkey_business()
 }
+
+runner::export_plugin!();
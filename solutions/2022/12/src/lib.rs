@@ -115,3 +115,5 @@ pub fn part2(input: &str) -> usize {
     }
     panic!("The 'a' level is not reachable")
 }
+
+runner::export_plugin!();
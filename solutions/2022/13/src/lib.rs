@@ -129,3 +129,5 @@ pub fn part2(input: &str) -> usize {
     input.sort();
     (input.binary_search(&marker_2).unwrap() + 1) * (input.binary_search(&marker_6).unwrap() + 1)
 }
+
+runner::export_plugin!();
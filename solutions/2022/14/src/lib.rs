@@ -174,3 +174,5 @@ pub fn part2(input: &str) -> usize {
 
     deposited_grains
 }
+
+runner::export_plugin!();
@@ -385,3 +385,5 @@ pub fn part2(input: &str) -> Result<PuzzleResult, Box<dyn Error>> {
 }
 
 */
+
+runner::export_plugin!();
@@ -186,3 +186,5 @@ pub fn part2(input: &str) -> usize {
     // find the best flow
     max_flow
 }
+
+runner::export_plugin!();
@@ -245,3 +245,5 @@ pub fn part2(input: &str) -> usize {
     // measuring heigth of the tower...
     first_empty_line + additional_height
 }
+
+runner::export_plugin!();
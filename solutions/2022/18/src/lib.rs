@@ -163,3 +163,5 @@ fn make_grid(cubes: &[(isize, isize, isize)]) -> (BoundingBox, Vec<bool>) {
     }
     (bb, grid)
 }
+
+runner::export_plugin!();
@@ -19,7 +19,7 @@ fn parse_input(input: &str) -> Vec<Blueprint> {
     }
     let mut blueprints = vec![];
     for (i, captures) in BLUEPRINT_RE.captures_iter(input).enumerate() {
-        if i + 1 != captures[1].parse().unwrap() {
+        if i + 1 != captures[1].parse::<usize>().unwrap() {
             panic!(
                 "Mismatched blueprint order: blueprint {} is in place {}",
                 &captures[1],
@@ -265,3 +265,5 @@ pub fn part2(input: &str) -> usize {
     });
     blueprints_geodes.fold(1, |acc, v| acc * v)
 }
+
+runner::export_plugin!();
@@ -120,3 +120,5 @@ pub fn part2(input: &str) -> i64 {
         })
         .sum()
 }
+
+runner::export_plugin!();
@@ -74,3 +74,5 @@ pub fn part2(input: &str) -> isize {
 
     list[p1].1 + list[p2].1 + list[p3].1
 }
+
+runner::export_plugin!();
@@ -577,3 +577,5 @@ pub fn part2(input: &str) -> isize {
         _ => unimplemented!("Solutions of other degrees are still unimplemented"),
     }
 }
+
+runner::export_plugin!();
@@ -668,3 +668,5 @@ fn plot(pos: Option<Pos>, field: &Grid<Tile>) {
         println!();
     }
 }
+
+runner::export_plugin!();
@@ -212,3 +212,5 @@ fn print_grid(grid: &Grid<bool>) {
         println!()
     }
 }
+
+runner::export_plugin!();
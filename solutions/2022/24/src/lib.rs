@@ -279,3 +279,5 @@ pub fn part2(input: &str) -> usize {
     // we hit dead end everywhere...
     panic!("No path found...")
 }
+
+runner::export_plugin!();
@@ -46,3 +46,5 @@ pub fn part1(input: &str) -> String {
             .sum(),
     )
 }
+
+runner::export_plugin!();
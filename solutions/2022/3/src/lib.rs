@@ -113,3 +113,5 @@ pub fn part2(input: &str) -> i64 {
     }
     total
 }
+
+runner::export_plugin!();
@@ -43,3 +43,5 @@ pub fn part2(input: &str) -> usize {
         .filter(|(r1, r2)| r1.overlap(*r2))
         .count()
 }
+
+runner::export_plugin!();
@@ -118,3 +118,5 @@ pub fn part2(input: &str) -> String {
     }
     stack_tops(stacks)
 }
+
+runner::export_plugin!();
@@ -1,14 +1,16 @@
-pub fn part<const WINDOW: usize>(input: &str) -> usize {
+pub fn part(input: &str, window: usize) -> usize {
     let input: Vec<_> = input.chars().collect();
-    'outer: for (pos, window) in input.windows(WINDOW).enumerate() {
-        for i in 0..window.len() {
+    'outer: for (pos, marker) in input.windows(window).enumerate() {
+        for i in 0..marker.len() {
             for j in 0..i {
-                if window[i] == window[j] {
+                if marker[i] == marker[j] {
                     continue 'outer;
                 }
             }
         }
-        return pos + WINDOW;
+        return pos + window;
     }
     panic!("Marker not found")
 }
+
+runner::export_plugin!();
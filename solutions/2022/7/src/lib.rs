@@ -191,3 +191,5 @@ pub fn part2(input: &str) -> usize {
     });
     smallest
 }
+
+runner::export_plugin!();
@@ -131,3 +131,5 @@ pub fn part2(input: &str) -> usize {
     }
     max_scenic_score
 }
+
+runner::export_plugin!();
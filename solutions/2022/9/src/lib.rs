@@ -134,3 +134,5 @@ pub fn part2(input: &str) -> usize {
 
     tail_positions.len()
 }
+
+runner::export_plugin!();
@@ -63,3 +63,5 @@ pub fn part2(input: &str) -> u32 {
         .map(|l| first_digit(l) * 10 + last_digit(l))
         .sum()
 }
+
+runner::export_plugin!();
@@ -94,3 +94,5 @@ pub fn part2(input: &str) -> usize {
         .map(|l| l.parse::<Game>().into_ok().minimum_total().power())
         .sum()
 }
+
+runner::export_plugin!();
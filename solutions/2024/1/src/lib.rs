@@ -27,3 +27,5 @@ pub fn part2(input: &str) -> usize {
         .map(|l| rlist.iter().filter(|r| *r == l).count() * l)
         .sum()
 }
+
+runner::export_plugin!();
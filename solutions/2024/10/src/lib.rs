@@ -123,3 +123,5 @@ pub fn part2(input: &str) -> usize {
 
     total_score
 }
+
+runner::export_plugin!();
@@ -46,6 +46,8 @@ fn split(number_written: usize) -> (usize, usize) {
     ((number_written - right) / tens, right)
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod tests {
     mod digits {
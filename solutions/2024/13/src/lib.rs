@@ -69,3 +69,5 @@ Prize: X=8400, Y=5400"
         280
     )
 }
+
+runner::export_plugin!();
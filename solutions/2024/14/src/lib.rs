@@ -14,12 +14,12 @@ fn parse(input: &str) -> impl Iterator<Item = ([i64; 2], [i64; 2])> + '_ {
     })
 }
 
-pub fn part1<const WIDTH: i64, const HEIGHT: i64, const SIM_LEN: i64>(input: &str) -> usize {
+pub fn part1(input: &str, width: i64, height: i64, sim_len: i64) -> usize {
     let mut quadrants: (usize, usize, usize, usize) = (0, 0, 0, 0);
     for ([px, py], [vx, vy]) in parse(input) {
-        let pfx = (px + SIM_LEN * vx).rem_euclid(WIDTH);
-        let pfy = (py + SIM_LEN * vy).rem_euclid(HEIGHT);
-        match (pfx.cmp(&(WIDTH / 2)), pfy.cmp(&(HEIGHT / 2))) {
+        let pfx = (px + sim_len * vx).rem_euclid(width);
+        let pfy = (py + sim_len * vy).rem_euclid(height);
+        match (pfx.cmp(&(width / 2)), pfy.cmp(&(height / 2))) {
             (std::cmp::Ordering::Less, std::cmp::Ordering::Less) => quadrants.0 += 1,
             (std::cmp::Ordering::Less, std::cmp::Ordering::Greater) => quadrants.1 += 1,
             (std::cmp::Ordering::Greater, std::cmp::Ordering::Less) => quadrants.2 += 1,
@@ -96,7 +96,7 @@ pub fn part2(input: &str) -> u64 {
 #[test]
 fn example() {
     assert_eq!(
-        part1::<11, 7, 100>(
+        part1(
             r"p=0,4 v=3,-3
 p=6,3 v=-1,-3
 p=10,3 v=-1,2
@@ -109,8 +109,13 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3
-"
+",
+            11,
+            7,
+            100
         ),
         12
     )
 }
+
+runner::export_plugin!();
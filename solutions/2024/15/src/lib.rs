@@ -307,3 +307,5 @@ fn edge_case_1() {
 ",
     );
 }
+
+runner::export_plugin!();
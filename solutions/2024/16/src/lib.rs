@@ -257,3 +257,5 @@ pub fn part2(input: &str) -> usize {
 
     panic!("No way to the end")
 }
+
+runner::export_plugin!();
@@ -308,3 +308,5 @@ fn a_value_to_emit<'a>(
         }),
     )
 }
+
+runner::export_plugin!();
@@ -88,3 +88,5 @@ pub fn part2(input: &str) -> String {
     input.lines().nth(min).unwrap().to_owned()
 
 }
+
+runner::export_plugin!();
@@ -84,3 +84,5 @@ pub fn part2(input: &str) -> usize {
         })
         .count()
 }
+
+runner::export_plugin!();
@@ -34,3 +34,5 @@ pub fn part2(mut input: &str) -> usize {
     }
     acc
 }
+
+runner::export_plugin!();
@@ -69,3 +69,5 @@ pub fn part2(input: &str) -> usize {
 
     counter
 }
+
+runner::export_plugin!();
@@ -88,3 +88,5 @@ fn sorting_fn(
         }
     }
 }
+
+runner::export_plugin!();
@@ -241,6 +241,8 @@ fn is_looping(mut grid: Grid<Cell2>, mut x: usize, mut y: usize, mut direction:
     }
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod tests {
     use crate::{part1, part2};
@@ -66,6 +66,8 @@ fn concat(a: usize, b: usize) -> usize {
     a * ten + b
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 mod tests {
     use crate::{can_be_built, can_be_built_with_concat, parse};
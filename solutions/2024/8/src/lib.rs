@@ -71,3 +71,5 @@ pub fn part2(input: &str) -> usize {
     }
     antinodes.len()
 }
+
+runner::export_plugin!();
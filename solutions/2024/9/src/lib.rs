@@ -140,6 +140,8 @@ pub fn part2(input: &str) -> usize {
         .sum()
 }
 
+runner::export_plugin!();
+
 #[cfg(test)]
 #[test]
 fn example_part2() {